@@ -0,0 +1,269 @@
+use crate::instancia::Instancia;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+/// Estructura correspondiente al analisis de conectividad de las aristas reales de una instancia.
+///
+/// Las aristas reales son las que no se penalizan, es decir, las que existen en la base de datos.
+///
+/// - ciudades:usize : Cantidad de ciudades de la instancia.
+/// - aristas:usize : Cantidad de aristas reales entre las ciudades de la instancia.
+/// - densidad:f64 : Proporcion de aristas reales respecto a las n(n-1)/2 aristas posibles.
+/// - componentes:`Vec<Vec<i64>>` : Componentes conexas, ordenadas de mayor a menor tamaño.
+/// - grados:`Vec<usize>` : Grado de cada ciudad, en el orden de las ciudades de la instancia.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Analisis {
+    pub ciudades: usize,
+    pub aristas: usize,
+    pub densidad: f64,
+    pub componentes: Vec<Vec<i64>>,
+    pub grados: Vec<usize>,
+}
+
+impl Analisis {
+
+    /// Constructor de la estructura.
+    ///
+    /// Recorre todos los pares de ciudades de la instancia para contar las aristas reales y los grados,
+    /// y obtiene las componentes conexas con una busqueda en profundidad.
+    ///
+    /// # Example
+    /// ```
+    /// let analisis = Analisis::new(&instancia);
+    /// ```
+    pub fn new(instancia: &Instancia) -> Self {
+        let n = instancia.len();
+        let ciudades = &instancia.ciudades;
+        let mut vecinas: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for i in 0..n {
+            for j in i + 1..n {
+                if instancia.existe_arista(ciudades[i], ciudades[j]) {
+                    vecinas[i].push(j);
+                    vecinas[j].push(i);
+                }
+            }
+        }
+        let grados: Vec<usize> = vecinas.iter().map(|v| v.len()).collect();
+        let aristas = grados.iter().sum::<usize>() / 2;
+        let posibles = n * n.saturating_sub(1) / 2;
+        let densidad = if posibles == 0 { 0.0 } else { aristas as f64 / posibles as f64 };
+
+        let mut visitada = vec![false; n];
+        let mut componentes: Vec<Vec<i64>> = Vec::new();
+        for inicial in 0..n {
+            if visitada[inicial] {
+                continue;
+            }
+            visitada[inicial] = true;
+            let mut pila = vec![inicial];
+            let mut componente = Vec::new();
+            while let Some(actual) = pila.pop() {
+                componente.push(ciudades[actual]);
+                for &vecina in &vecinas[actual] {
+                    if !visitada[vecina] {
+                        visitada[vecina] = true;
+                        pila.push(vecina);
+                    }
+                }
+            }
+            componente.sort();
+            componentes.push(componente);
+        }
+        componentes.sort_by_key(|c| std::cmp::Reverse(c.len()));
+
+        Analisis { ciudades: n, aristas, densidad, componentes, grados }
+    }
+
+    /// Funcion encargada de obtener la distribucion de grados.
+    ///
+    /// Regresa un vector en el que la entrada i es la cantidad de ciudades con grado i.
+    ///
+    pub fn distribucion_grados(&self) -> Vec<usize> {
+        let mut distribucion = vec![0; self.grados.iter().max().map_or(0, |g| g + 1)];
+        for &grado in &self.grados {
+            distribucion[grado] += 1;
+        }
+        distribucion
+    }
+
+    /// Funcion encargada de obtener los motivos por los que no puede existir un camino hamiltoniano con aristas reales.
+    ///
+    /// Revisa condiciones necesarias: que la grafica sea conexa, que no haya ciudades aisladas y que a lo mas dos ciudades tengan grado 1,
+    /// pues unicamente los extremos del camino pueden tener una sola vecina. Si la lista es vacia el camino es plausible, aunque no se garantiza que exista.
+    ///
+    pub fn obstaculos_hamiltoniano(&self) -> Vec<String> {
+        let mut obstaculos = Vec::new();
+        if self.ciudades < 2 {
+            return obstaculos;
+        }
+        if self.componentes.len() > 1 {
+            obstaculos.push(format!("La grafica tiene {} componentes conexas", self.componentes.len()));
+        }
+        let aisladas = self.grados.iter().filter(|g| **g == 0).count();
+        if aisladas > 0 {
+            obstaculos.push(format!("{} ciudades no tienen aristas reales", aisladas));
+        }
+        let hojas = self.grados.iter().filter(|g| **g == 1).count();
+        if hojas > 2 {
+            obstaculos.push(format!("{} ciudades tienen grado 1 y un camino tiene a lo mas 2 extremos", hojas));
+        }
+        obstaculos
+    }
+
+    /// Funcion encargada de indicar si es plausible un camino hamiltoniano que use unicamente aristas reales.
+    ///
+    pub fn hamiltoniano_plausible(&self) -> bool {
+        self.obstaculos_hamiltoniano().is_empty()
+    }
+}
+
+/// Funcion encargada de obtener las aristas penalizadas de un recorrido.
+///
+/// Recibe la instancia y el recorrido, y regresa los pares de ciudades consecutivas del recorrido cuya arista no existe.
+///
+/// # Example
+/// ```
+/// let penalizadas = aristas_penalizadas(&instancia, &resultado.solucion);
+/// ```
+pub fn aristas_penalizadas(instancia: &Instancia, recorrido: &[i64]) -> Vec<(i64, i64)> {
+    recorrido.windows(2).filter(|par| !instancia.existe_arista(par[0], par[1])).map(|par| (par[0], par[1])).collect()
+}
+
+/// Estructura correspondiente a una arista de un recorrido evaluada.
+///
+/// - origen:i64 : Ciudad en la que inicia la arista.
+/// - destino:i64 : Ciudad en la que termina la arista.
+/// - peso:f64 : Peso de la arista, la distancia de la base de datos o la distancia penalizada.
+/// - penalizada:bool : Indica si la arista no existe y su peso es la distancia natural multiplicada por la distancia maxima.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct AristaEvaluada {
+    pub origen: i64,
+    pub destino: i64,
+    pub peso: f64,
+    pub penalizada: bool,
+}
+
+/// Estructura correspondiente a la evaluacion de un recorrido arista por arista.
+///
+/// - aristas:`Vec<AristaEvaluada>` : Aristas del recorrido en orden.
+/// - longitud:f64 : Suma de los pesos de las aristas, sin normalizar.
+/// - costo:f64 : Longitud dividida entre el normalizador de la instancia, el mismo valor que `Tsp::calcular_solucion`.
+/// - penalizadas:usize : Cantidad de aristas penalizadas.
+/// - normalizador:f64 : Normalizador de la instancia.
+/// - distancia_maxima:f64 : Distancia maxima de la instancia, con la que se multiplican las aristas penalizadas.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evaluacion {
+    pub aristas: Vec<AristaEvaluada>,
+    pub longitud: f64,
+    pub costo: f64,
+    pub penalizadas: usize,
+    pub normalizador: f64,
+    pub distancia_maxima: f64,
+}
+
+impl Evaluacion {
+
+    /// Constructor de la estructura.
+    ///
+    /// Recibe la instancia y el recorrido, y evalua cada par de ciudades consecutivas del recorrido.
+    ///
+    /// # Example
+    /// ```
+    /// let evaluacion = Evaluacion::new(&instancia, &resultado.solucion);
+    /// ```
+    pub fn new(instancia: &Instancia, recorrido: &[i64]) -> Self {
+        let aristas: Vec<AristaEvaluada> = recorrido
+            .windows(2)
+            .map(|par| AristaEvaluada { origen: par[0], destino: par[1], peso: instancia.peso(par[0], par[1]), penalizada: !instancia.existe_arista(par[0], par[1]) })
+            .collect();
+        let longitud: f64 = aristas.iter().map(|a| a.peso).sum();
+        let penalizadas = aristas.iter().filter(|a| a.penalizada).count();
+        Evaluacion { aristas, longitud, costo: longitud / instancia.normalizador, penalizadas, normalizador: instancia.normalizador, distancia_maxima: instancia.distancia_maxima }
+    }
+
+    /// Funcion encargada de indicar si el recorrido es factible, es decir, si no tiene aristas penalizadas.
+    ///
+    pub fn factible(&self) -> bool {
+        self.penalizadas == 0
+    }
+
+    /// Funcion encargada de escribir la evaluacion de cada arista en un archivo csv.
+    ///
+    /// Cada renglon tiene el origen, el destino, el peso, si la arista esta penalizada y, en ese caso, la distancia natural
+    /// que se multiplico por la distancia maxima.
+    ///
+    /// # Example
+    /// ```
+    /// evaluacion.escribir_csv("resultados/tsp_semilla_1_aristas.csv")?;
+    /// ```
+    pub fn escribir_csv(&self, ruta: &str) -> std::io::Result<()> {
+        let mut file = BufWriter::new(File::create(ruta)?);
+        writeln!(file, "origen,destino,peso,penalizada,distancia_natural")?;
+        for arista in &self.aristas {
+            let natural = if arista.penalizada { (arista.peso / self.distancia_maxima).to_string() } else { String::new() };
+            writeln!(file, "{},{},{},{},{}", arista.origen, arista.destino, arista.peso, arista.penalizada, natural)?;
+        }
+        file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generar_instancia(aristas_reales: &[(usize, usize)], n: usize) -> Instancia {
+        let ciudades: Vec<i64> = (1..=n as i64).collect();
+        let coordenadas: Vec<(f64, f64)> = (0..n).map(|i| (i as f64, 0.0)).collect();
+        let mut distancias = vec![0.0; n * n];
+        let mut aristas = vec![false; n * n];
+        for i in 0..n {
+            for j in 0..n {
+                distancias[i * n + j] = (i as f64 - j as f64).abs();
+            }
+        }
+        for &(i, j) in aristas_reales {
+            aristas[i * n + j] = true;
+            aristas[j * n + i] = true;
+        }
+        Instancia::new("prueba", ciudades, coordenadas, distancias, aristas)
+    }
+
+    #[test]
+    fn ok_analisis() {
+        let instancia = generar_instancia(&[(0, 1), (1, 2), (3, 4)], 6);
+        let analisis = Analisis::new(&instancia);
+        assert_eq!(analisis.aristas, 3);
+        assert_eq!(analisis.densidad, 3.0 / 15.0);
+        assert_eq!(analisis.componentes, vec![vec![1, 2, 3], vec![4, 5], vec![6]]);
+        assert_eq!(analisis.grados, vec![1, 2, 1, 1, 1, 0]);
+        assert_eq!(analisis.distribucion_grados(), vec![1, 4, 1]);
+        assert_eq!(analisis.obstaculos_hamiltoniano().len(), 3);
+        assert!(!analisis.hamiltoniano_plausible());
+
+        let camino = generar_instancia(&[(0, 1), (1, 2), (2, 3)], 4);
+        assert!(Analisis::new(&camino).hamiltoniano_plausible());
+    }
+
+    #[test]
+    fn ok_aristas_penalizadas() {
+        let instancia = generar_instancia(&[(0, 1), (1, 2), (3, 4)], 6);
+        assert_eq!(aristas_penalizadas(&instancia, &[1, 2, 3, 4, 5, 6]), vec![(3, 4), (5, 6)]);
+        assert!(aristas_penalizadas(&instancia, &[3, 2, 1]).is_empty());
+    }
+
+    #[test]
+    fn ok_evaluacion() {
+        let instancia = generar_instancia(&[(0, 1), (1, 2), (3, 4)], 6);
+        let evaluacion = Evaluacion::new(&instancia, &[1, 2, 3, 4]);
+        assert_eq!(evaluacion.aristas.len(), 3);
+        assert_eq!(evaluacion.longitud, 3.0);
+        assert_eq!(evaluacion.costo, 3.0 / instancia.normalizador);
+        assert_eq!(evaluacion.penalizadas, 1);
+        assert!(evaluacion.aristas[2].penalizada);
+        assert!(!evaluacion.factible());
+        assert!(Evaluacion::new(&instancia, &[3, 2, 1]).factible());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::resultado::Resultado;
+use chrono::Local;
+use rusqlite::{params, Connection, Result};
+
+/// Estructura correspondiente a una ejecucion almacenada en la base de resultados.
+///
+/// - id:i64 : Identificador de la ejecucion.
+/// - instancia:String : Nombre del archivo de ciudades.
+/// - semilla:i64 : Semilla de la ejecucion.
+/// - costo:f64 : Peso de la mejor solucion.
+/// - tiempo:f64 : Tiempo total en segundos.
+/// - fecha:String : Fecha en la que se registro la ejecucion.
+///
+pub struct Ejecucion {
+    pub id: i64,
+    pub instancia: String,
+    pub semilla: i64,
+    pub costo: f64,
+    pub tiempo: f64,
+    pub fecha: String,
+}
+
+/// Estructura correspondiente a la base de datos de resultados.
+///
+/// Almacena las ejecuciones en la tabla runs, el recorrido de cada ejecucion en la tabla tours
+/// y, de forma opcional, la traza de soluciones aceptadas en la tabla trace.
+///
+pub struct BaseResultados {
+    conn: Connection,
+}
+
+impl BaseResultados {
+
+    /// Funcion encargada de abrir la base de resultados.
+    ///
+    /// Crea las tablas en caso de que no existan.
+    ///
+    /// # Example
+    /// ```
+    /// let base = BaseResultados::abrir("resultados/resultados.db")?;
+    /// ```
+    pub fn abrir(ruta: &str) -> Result<Self> {
+        let conn = Connection::open(ruta)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                instancia TEXT NOT NULL,
+                hash_instancia TEXT NOT NULL,
+                hash_parametros TEXT NOT NULL,
+                semilla INTEGER NOT NULL,
+                parametros TEXT NOT NULL,
+                temperatura_inicial REAL NOT NULL,
+                temperatura_final REAL NOT NULL,
+                costo_antes_barrido REAL NOT NULL,
+                costo REAL NOT NULL,
+                lotes INTEGER NOT NULL,
+                aceptadas INTEGER NOT NULL,
+                tiempo REAL NOT NULL,
+                fecha TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS runs_instancia ON runs (hash_instancia, costo);
+            CREATE TABLE IF NOT EXISTS tours (
+                run_id INTEGER NOT NULL REFERENCES runs(id),
+                posicion INTEGER NOT NULL,
+                ciudad INTEGER NOT NULL,
+                PRIMARY KEY (run_id, posicion)
+            );
+            CREATE TABLE IF NOT EXISTS trace (
+                run_id INTEGER NOT NULL REFERENCES runs(id),
+                indice INTEGER NOT NULL,
+                valor REAL NOT NULL,
+                es_mejora INTEGER NOT NULL,
+                PRIMARY KEY (run_id, indice)
+            );",
+        )?;
+        Ok(BaseResultados { conn })
+    }
+
+    /// Funcion encargada de registrar una ejecucion.
+    ///
+    /// Inserta la ejecucion, su recorrido y, si se proporciona, la traza de soluciones aceptadas dentro de una transaccion.
+    /// Regresa el identificador de la ejecucion.
+    ///
+    /// # Example
+    /// ```
+    /// let id = base.registrar(&resultado, None)?;
+    /// ```
+    pub fn registrar(&mut self, resultado: &Resultado, traza: Option<&[(f64, bool)]>) -> Result<i64> {
+        let parametros = serde_json::to_string(&resultado.parametros).unwrap_or_default();
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO runs (instancia, hash_instancia, hash_parametros, semilla, parametros, temperatura_inicial,
+                temperatura_final, costo_antes_barrido, costo, lotes, aceptadas, tiempo, fecha)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                resultado.instancia,
+                resultado.hash_instancia,
+                resultado.hash_parametros,
+                resultado.semilla,
+                parametros,
+                resultado.temperatura_inicial,
+                resultado.temperatura_final,
+                resultado.costo_antes_barrido,
+                resultado.costo,
+                resultado.lotes as i64,
+                resultado.aceptadas as i64,
+                resultado.tiempo,
+                Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        {
+            let mut stmt = tx.prepare("INSERT INTO tours (run_id, posicion, ciudad) VALUES (?1, ?2, ?3)")?;
+            for (posicion, ciudad) in resultado.solucion.iter().enumerate() {
+                stmt.execute(params![id, posicion as i64, ciudad])?;
+            }
+            if let Some(traza) = traza {
+                let mut stmt = tx.prepare("INSERT INTO trace (run_id, indice, valor, es_mejora) VALUES (?1, ?2, ?3, ?4)")?;
+                for (indice, (valor, es_mejora)) in traza.iter().enumerate() {
+                    stmt.execute(params![id, indice as i64, valor, es_mejora])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Funcion encargada de obtener las k mejores ejecuciones de una instancia.
+    ///
+    /// Las ejecuciones se ordenan de menor a mayor costo.
+    ///
+    /// # Example
+    /// ```
+    /// let mejores = base.mejores(&hash_instancia(&ciudades), 10)?;
+    /// ```
+    pub fn mejores(&self, hash_instancia: &str, k: usize) -> Result<Vec<Ejecucion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, instancia, semilla, costo, tiempo, fecha FROM runs
+             WHERE hash_instancia = ?1 ORDER BY costo ASC, id ASC LIMIT ?2",
+        )?;
+        let ejecuciones = stmt.query_map(params![hash_instancia, k as i64], |row| {
+            Ok(Ejecucion {
+                id: row.get(0)?,
+                instancia: row.get(1)?,
+                semilla: row.get(2)?,
+                costo: row.get(3)?,
+                tiempo: row.get(4)?,
+                fecha: row.get(5)?,
+            })
+        })?;
+        ejecuciones.collect()
+    }
+
+    /// Funcion encargada de obtener el recorrido de una ejecucion.
+    ///
+    pub fn recorrido(&self, id: i64) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare("SELECT ciudad FROM tours WHERE run_id = ?1 ORDER BY posicion")?;
+        let ciudades = stmt.query_map([id], |row| row.get(0))?;
+        ciudades.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_registrar_y_mejores() {
+        let mut base = BaseResultados::abrir(":memory:").unwrap();
+        let peor = Resultado { hash_instancia: "a".to_string(), semilla: 1, costo: 2.0, solucion: vec![3, 1, 2], ..Resultado::default() };
+        let mejor = Resultado { hash_instancia: "a".to_string(), semilla: 2, costo: 1.0, solucion: vec![1, 2, 3], ..Resultado::default() };
+        let otra = Resultado { hash_instancia: "b".to_string(), semilla: 3, costo: 0.5, ..Resultado::default() };
+
+        base.registrar(&peor, None).unwrap();
+        let id = base.registrar(&mejor, Some(&[(3.0, true), (2.0, true)])).unwrap();
+        base.registrar(&otra, None).unwrap();
+
+        let mejores = base.mejores("a", 1).unwrap();
+        assert_eq!(mejores.len(), 1);
+        assert_eq!(mejores[0].semilla, 2);
+        assert_eq!(mejores[0].id, id);
+        assert_eq!(base.recorrido(id).unwrap(), vec![1, 2, 3]);
+        assert_eq!(base.mejores("a", 10).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Estructura correspondiente a un token de cancelacion compartido entre hilos.
+///
+/// Todas las copias del token comparten el mismo estado, de tal manera que al cancelar una de ellas
+/// (por ejemplo desde el manejador de Ctrl-C) todos los recocidos que la revisan terminan.
+///
+/// # Example
+/// ```
+/// let cancelacion = Cancelacion::new();
+/// tsp.establecer_cancelacion(cancelacion.clone());
+/// cancelacion.cancelar();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Cancelacion {
+    cancelado: Arc<AtomicBool>,
+}
+
+impl Cancelacion {
+
+    /// Constructor del token de cancelacion, inicialmente sin cancelar.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Funcion encargada de cancelar el token y todas sus copias.
+    ///
+    pub fn cancelar(&self) {
+        self.cancelado.store(true, Ordering::Relaxed);
+    }
+
+    /// Funcion encargada de indicar si el token fue cancelado.
+    ///
+    pub fn cancelado(&self) -> bool {
+        self.cancelado.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_cancelar() {
+        let cancelacion = Cancelacion::new();
+        let copia = cancelacion.clone();
+        assert!(!copia.cancelado());
+        cancelacion.cancelar();
+        assert!(copia.cancelado());
+    }
+}
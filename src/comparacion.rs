@@ -0,0 +1,320 @@
+use crate::resultado::Resultado;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+
+/// Cantidad de puntos con los que se muestrea la curva de la mejor solucion de cada semilla.
+pub const PUNTOS_CURVA: usize = 200;
+
+/// Funcion encargada de calcular la curva de la mejor solucion encontrada hasta cada momento.
+///
+/// Recibe como parametros lo siguiente:
+/// - datos:`&[(f64, bool)]` : Muestra de la traza, peso de las soluciones aceptadas y si fue una mejora de la mejor solucion.
+/// - puntos:usize : Cantidad de puntos de la curva.
+///
+/// El punto i de la curva corresponde a la mejor solucion encontrada al haber recorrido la fraccion i/(puntos-1)
+/// de las soluciones aceptadas, de esta manera las curvas de semillas con distinta cantidad de soluciones aceptadas
+/// pueden compararse punto a punto. Si la traza esta decimada cada punto es el peso promedio de un bloque, por lo que
+/// la curva es el minimo acumulado de esos promedios y puede quedar por encima de la mejor solucion dentro de cada bloque.
+///
+/// # Example
+/// ```
+/// let (datos, _) = tsp.tomar_traza().muestra(100_000)?;
+/// let curva = curva_mejor(&datos, PUNTOS_CURVA);
+/// ```
+pub fn curva_mejor(datos: &[(f64, bool)], puntos: usize) -> Vec<f64> {
+    if datos.is_empty() || puntos == 0 {
+        return Vec::new();
+    }
+    let mut minimos = Vec::with_capacity(datos.len());
+    let mut minimo = f64::INFINITY;
+    for (valor, _) in datos {
+        minimo = minimo.min(*valor);
+        minimos.push(minimo);
+    }
+    let ultimo = minimos.len() - 1;
+    (0..puntos)
+        .map(|i| {
+            let indice = if puntos == 1 { ultimo } else { i * ultimo / (puntos - 1) };
+            minimos[indice]
+        })
+        .collect()
+}
+
+/// Funcion encargada de calcular un percentil por interpolacion lineal.
+///
+/// Los valores deben estar ordenados de menor a mayor y el percentil estar entre 0 y 1.
+///
+fn percentil(ordenados: &[f64], p: f64) -> f64 {
+    if ordenados.is_empty() {
+        return f64::NAN;
+    }
+    let posicion = p.clamp(0.0, 1.0) * (ordenados.len() - 1) as f64;
+    let abajo = posicion.floor() as usize;
+    let arriba = posicion.ceil() as usize;
+    ordenados[abajo] + (ordenados[arriba] - ordenados[abajo]) * (posicion - abajo as f64)
+}
+
+/// Estructura correspondiente a las curvas de un conjunto de semillas de una misma configuracion.
+///
+/// - nombre:String : Nombre de la configuracion, se muestra en la leyenda y en el diagrama de caja.
+/// - semillas:`Vec<i64>` : Semillas del conjunto.
+/// - costos:`Vec<f64>` : Peso final de cada semilla, despues del barrido.
+/// - curvas:`Vec<Vec<f64>>` : Curva de la mejor solucion de cada semilla.
+///
+pub struct Curvas {
+    pub nombre: String,
+    pub semillas: Vec<i64>,
+    pub costos: Vec<f64>,
+    pub curvas: Vec<Vec<f64>>,
+}
+
+impl Curvas {
+
+    /// Funcion encargada de calcular la banda de percentiles de las curvas.
+    ///
+    /// Regresa para cada punto el percentil 10, la mediana y el percentil 90 de las curvas.
+    ///
+    fn banda(&self) -> Vec<(f64, f64, f64)> {
+        let puntos = self.curvas.iter().map(|c| c.len()).min().unwrap_or(0);
+        (0..puntos)
+            .map(|i| {
+                let mut valores: Vec<f64> = self.curvas.iter().map(|c| c[i]).collect();
+                valores.sort_by(|a, b| a.total_cmp(b));
+                (percentil(&valores, 0.1), percentil(&valores, 0.5), percentil(&valores, 0.9))
+            })
+            .collect()
+    }
+}
+
+/// Funcion encargada de escribir las curvas de un conjunto de semillas en un archivo csv.
+///
+/// Cada renglon contiene la semilla, el peso final y los puntos de la curva de la mejor solucion,
+/// de tal manera que la grafica de comparacion pueda regenerarse sin volver a ejecutar las semillas.
+///
+/// # Example
+/// ```
+/// escribir_curvas(&resultados, "resultados/tsp_curvas.csv")?;
+/// ```
+pub fn escribir_curvas(resultados: &[Resultado], ruta: &str) -> std::io::Result<()> {
+    let mut file = BufWriter::new(File::create(ruta)?);
+    let puntos = resultados.iter().map(|r| r.curva_mejor.len()).max().unwrap_or(0);
+    let encabezado: Vec<String> = (0..puntos).map(|i| format!("p{}", i)).collect();
+    writeln!(file, "semilla,costo{}{}", if puntos > 0 { "," } else { "" }, encabezado.join(","))?;
+    for resultado in resultados {
+        let curva: Vec<String> = resultado.curva_mejor.iter().map(|v| v.to_string()).collect();
+        writeln!(file, "{},{}{}{}", resultado.semilla, resultado.costo, if curva.is_empty() { "" } else { "," }, curva.join(","))?;
+    }
+    file.flush()
+}
+
+/// Funcion encargada de leer las curvas de un conjunto de semillas de un archivo csv.
+///
+/// El nombre de la configuracion es el nombre del archivo sin la extension ni el sufijo `_curvas`.
+///
+/// # Example
+/// ```
+/// let curvas = leer_curvas("resultados/tsp_curvas.csv")?;
+/// ```
+pub fn leer_curvas(ruta: &str) -> std::io::Result<Curvas> {
+    let contenido = fs::read_to_string(ruta)?;
+    let nombre = std::path::Path::new(ruta)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .map(|s| s.trim_end_matches("_curvas").to_string())
+        .unwrap_or_else(|| ruta.to_string());
+    let mut curvas = Curvas { nombre, semillas: Vec::new(), costos: Vec::new(), curvas: Vec::new() };
+    for linea in contenido.lines().skip(1).filter(|l| !l.trim().is_empty()) {
+        let mut campos = linea.split(',');
+        let invalido = || std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Renglon invalido en {}: {}", ruta, linea));
+        let semilla = campos.next().and_then(|c| c.trim().parse::<i64>().ok()).ok_or_else(invalido)?;
+        let costo = campos.next().and_then(|c| c.trim().parse::<f64>().ok()).ok_or_else(invalido)?;
+        let curva = campos.map(|c| c.trim().parse::<f64>()).collect::<Result<Vec<f64>, _>>().map_err(|_| invalido())?;
+        curvas.semillas.push(semilla);
+        curvas.costos.push(costo);
+        curvas.curvas.push(curva);
+    }
+    Ok(curvas)
+}
+
+/// Funcion encargada de dibujar la grafica de comparacion en un area de dibujo.
+///
+/// El area se divide en dos paneles:
+/// - Arriba, la curva de la mejor solucion de cada semilla de forma tenue, junto con la mediana
+///   y la banda entre los percentiles 10 y 90 de cada configuracion.
+/// - Abajo, un diagrama de caja del peso final de las semillas de cada configuracion.
+///
+fn dibujar<DB: DrawingBackend>(raiz: DrawingArea<DB, Shift>, configuraciones: &[Curvas], titulo: &str) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    raiz.fill(&WHITE)?;
+    let raiz = raiz.titled(titulo, ("sans-serif", 28))?;
+    let (superior, inferior) = raiz.split_vertically((raiz.dim_in_pixel().1 as f64 * 0.6) as u32);
+
+    let (min_y, max_y) = configuraciones
+        .iter()
+        .flat_map(|c| c.curvas.iter().flatten().chain(c.costos.iter()))
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(*v), max.max(*v)));
+    let max_y = max_y.max(min_y + f64::EPSILON);
+
+    let mut grafica = ChartBuilder::on(&superior)
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(90)
+        .build_cartesian_2d(0.0..100.0, min_y..max_y)?;
+
+    grafica
+        .configure_mesh()
+        .x_desc("Porcentaje de soluciones aceptadas")
+        .y_desc("Mejor solucion")
+        .draw()?;
+
+    for (i, configuracion) in configuraciones.iter().enumerate() {
+        let color = Palette99::pick(i);
+        for curva in &configuracion.curvas {
+            let escala = 100.0 / (curva.len().max(2) - 1) as f64;
+            grafica.draw_series(LineSeries::new(
+                curva.iter().enumerate().map(|(j, v)| (j as f64 * escala, *v)),
+                color.mix(0.15),
+            ))?;
+        }
+
+        let banda = configuracion.banda();
+        if banda.is_empty() {
+            continue;
+        }
+        let escala = 100.0 / (banda.len().max(2) - 1) as f64;
+        let contorno: Vec<(f64, f64)> = banda
+            .iter()
+            .enumerate()
+            .map(|(j, (p10, _, _))| (j as f64 * escala, *p10))
+            .chain(banda.iter().enumerate().rev().map(|(j, (_, _, p90))| (j as f64 * escala, *p90)))
+            .collect();
+        grafica.draw_series(std::iter::once(Polygon::new(contorno, color.mix(0.25).filled())))?;
+        grafica
+            .draw_series(LineSeries::new(
+                banda.iter().enumerate().map(|(j, (_, mediana, _))| (j as f64 * escala, *mediana)),
+                color.stroke_width(2),
+            ))?
+            .label(format!("{} (mediana, p10-p90)", configuracion.nombre))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+
+    grafica
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    let (min_c, max_c) = configuraciones
+        .iter()
+        .filter(|c| !c.costos.is_empty())
+        .flat_map(|c| Quartiles::new(&c.costos).values().into_iter().map(f64::from).chain(c.costos.iter().copied()))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(v), max.max(v)));
+    let margen = ((max_c - min_c) * 0.1).max(f64::EPSILON);
+    let nombres: Vec<String> = configuraciones.iter().map(|c| c.nombre.clone()).collect();
+    let mut grafica_cajas = ChartBuilder::on(&inferior)
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(90)
+        .build_cartesian_2d(nombres[..].into_segmented(), (min_c - margen) as f32..(max_c + margen) as f32)?;
+
+    grafica_cajas
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_label_formatter(&|valor| match valor {
+            SegmentValue::Exact(nombre) | SegmentValue::CenterOf(nombre) => nombre.to_string(),
+            SegmentValue::Last => String::new(),
+        })
+        .x_desc("Configuracion")
+        .y_desc("Peso final")
+        .draw()?;
+
+    grafica_cajas.draw_series(configuraciones.iter().enumerate().filter(|(_, c)| !c.costos.is_empty()).map(|(i, c)| {
+        Boxplot::new_vertical(SegmentValue::CenterOf(&nombres[i]), &Quartiles::new(&c.costos))
+            .width(40)
+            .style(Palette99::pick(i).stroke_width(2))
+    }))?;
+
+    raiz.present()?;
+    Ok(())
+}
+
+/// Funcion encargada de generar la grafica de comparacion de varias configuraciones.
+///
+/// Recibe como parametros lo siguiente:
+/// - configuraciones:`&[Curvas]` : Curvas de cada configuracion a comparar.
+/// - nombre:&str : Nombre de los archivos a generar dentro de la carpeta svgs.
+///
+/// Genera la misma grafica en `svgs/<nombre>.svg` y en `svgs/<nombre>.png`.
+///
+/// # Example
+/// ```
+/// generar_comparacion(&[leer_curvas("resultados/tsp_curvas.csv")?], "tsp_comparacion")?;
+/// ```
+pub fn generar_comparacion(configuraciones: &[Curvas], nombre: &str) -> Result<(), Box<dyn Error>> {
+    if configuraciones.iter().all(|c| c.costos.is_empty()) {
+        println!("No hay datos para generar la grafica de comparacion.");
+        return Ok(());
+    }
+    let semillas: usize = configuraciones.iter().map(|c| c.semillas.len()).sum();
+    let titulo = format!("Comparacion de {} semillas", semillas);
+
+    let ruta_svg = format!("svgs/{}.svg", nombre);
+    dibujar(SVGBackend::new(&ruta_svg, (1600, 1200)).into_drawing_area(), configuraciones, &titulo)?;
+
+    let ruta_png = format!("svgs/{}.png", nombre);
+    dibujar(BitMapBackend::new(&ruta_png, (1600, 1200)).into_drawing_area(), configuraciones, &titulo)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_curva_mejor() {
+        let datos = vec![(5.0, true), (6.0, false), (4.0, true), (4.5, false), (3.0, true)];
+        assert_eq!(curva_mejor(&datos, 5), vec![5.0, 5.0, 4.0, 4.0, 3.0]);
+        assert_eq!(curva_mejor(&datos, 3), vec![5.0, 4.0, 3.0]);
+        assert_eq!(curva_mejor(&datos, 1), vec![3.0]);
+        assert!(curva_mejor(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn ok_percentil() {
+        let valores = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(percentil(&valores, 0.5), 3.0);
+        assert_eq!(percentil(&valores, 0.0), 1.0);
+        assert_eq!(percentil(&valores, 1.0), 5.0);
+        assert!((percentil(&valores, 0.1) - 1.4).abs() < 1e-12);
+        assert!(percentil(&[], 0.5).is_nan());
+    }
+
+    #[test]
+    fn ok_escribir_y_leer_curvas() {
+        let ruta = std::env::temp_dir().join(format!("prueba_{}_curvas.csv", std::process::id()));
+        let ruta = ruta.to_str().unwrap();
+        let resultados = vec![
+            Resultado { semilla: 1, costo: 2.0, curva_mejor: vec![3.0, 2.5], ..Resultado::default() },
+            Resultado { semilla: 2, costo: 1.5, curva_mejor: vec![4.0, 1.5], ..Resultado::default() },
+        ];
+        escribir_curvas(&resultados, ruta).unwrap();
+        let curvas = leer_curvas(ruta).unwrap();
+        assert_eq!(curvas.nombre, format!("prueba_{}", std::process::id()));
+        assert_eq!(curvas.semillas, vec![1, 2]);
+        assert_eq!(curvas.costos, vec![2.0, 1.5]);
+        assert_eq!(curvas.curvas, vec![vec![3.0, 2.5], vec![4.0, 1.5]]);
+        let banda = curvas.banda();
+        let esperada = [(3.1, 3.5, 3.9), (1.6, 2.0, 2.4)];
+        assert_eq!(banda.len(), 2);
+        for ((p10, mediana, p90), (e10, emediana, e90)) in banda.iter().zip(esperada) {
+            assert!((p10 - e10).abs() < 1e-12 && (mediana - emediana).abs() < 1e-12 && (p90 - e90).abs() < 1e-12);
+        }
+        fs::remove_file(ruta).unwrap();
+    }
+}
@@ -0,0 +1,163 @@
+use crate::tsp::Lote;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::error::Error;
+
+/// Cantidad maxima de puntos de la curva de costos que se dibujan.
+const MAX_PUNTOS: usize = 4000;
+
+/// Funcion encargada de reducir la cantidad de puntos de una serie.
+///
+/// Toma un punto cada cierto paso de tal manera que la serie no exceda el maximo de puntos,
+/// conservando siempre el ultimo punto.
+///
+fn reducir<T: Copy>(puntos: &[T], max_puntos: usize) -> Vec<T> {
+    let paso = puntos.len().div_ceil(max_puntos.max(1)).max(1);
+    let mut reducidos: Vec<T> = puntos.iter().step_by(paso).copied().collect();
+    if let Some(ultimo) = puntos.last()
+        && !(puntos.len() - 1).is_multiple_of(paso)
+    {
+        reducidos.push(*ultimo);
+    }
+    reducidos
+}
+
+/// Funcion encargada de dibujar la grafica de convergencia en un area de dibujo.
+///
+/// El area se divide en dos paneles que comparten el eje x (numero de solucion aceptada):
+/// - Arriba, el peso de las soluciones aceptadas, las mejoras de la mejor solucion como puntos rojos
+///   y la temperatura de cada lote en el eje secundario en escala logaritmica.
+/// - Abajo, la tasa de aceptacion de cada lote.
+///
+fn dibujar<DB: DrawingBackend>(raiz: DrawingArea<DB, Shift>, datos: &[(f64, bool)], paso: usize, lotes: &[Lote], titulo: &str) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    raiz.fill(&WHITE)?;
+    let raiz = raiz.titled(titulo, ("sans-serif", 28))?;
+    let (superior, inferior) = raiz.split_vertically((raiz.dim_in_pixel().1 as f64 * 0.7) as u32);
+
+    let max_x = (datos.len() * paso).max(1) as f64;
+    let (min_y, max_y) = datos.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), (valor, _)| {
+        (min.min(*valor), max.max(*valor))
+    });
+    let (min_t, max_t) = lotes.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), lote| {
+        (min.min(lote.temperatura), max.max(lote.temperatura))
+    });
+    let (min_t, max_t) = if min_t.is_finite() && min_t > 0.0 { (min_t, max_t.max(min_t * 10.0)) } else { (1e-4, 1.0) };
+
+    let mut grafica = ChartBuilder::on(&superior)
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(90)
+        .right_y_label_area_size(90)
+        .build_cartesian_2d(0.0..max_x, min_y..max_y.max(min_y + f64::EPSILON))?
+        .set_secondary_coord(0.0..max_x, (min_t..max_t).log_scale());
+
+    grafica
+        .configure_mesh()
+        .x_desc("Solucion aceptada")
+        .y_desc("Peso solucion")
+        .draw()?;
+    grafica
+        .configure_secondary_axes()
+        .y_desc("Temperatura")
+        .draw()?;
+
+    let puntos: Vec<(f64, f64)> = datos.iter().enumerate().map(|(i, (valor, _))| ((i * paso) as f64, *valor)).collect();
+    grafica
+        .draw_series(LineSeries::new(reducir(&puntos, MAX_PUNTOS), &BLUE))?
+        .label("Peso solucion")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE));
+
+    let mejoras: Vec<(f64, f64)> = datos
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, es_mejora))| *es_mejora)
+        .map(|(i, (valor, _))| ((i * paso) as f64, *valor))
+        .collect();
+    grafica
+        .draw_series(reducir(&mejoras, MAX_PUNTOS).into_iter().map(|p| Circle::new(p, 3, RED.filled())))?
+        .label("Mejor solucion")
+        .legend(|(x, y)| Circle::new((x + 10, y), 3, RED.filled()));
+
+    grafica
+        .draw_secondary_series(LineSeries::new(
+            lotes.iter().map(|lote| (lote.aceptadas as f64, lote.temperatura)),
+            &GREEN,
+        ))?
+        .label("Temperatura")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], GREEN));
+
+    grafica
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    let mut grafica_tasa = ChartBuilder::on(&inferior)
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(90)
+        .right_y_label_area_size(90)
+        .build_cartesian_2d(0.0..max_x, 0.0..1.0)?;
+
+    grafica_tasa
+        .configure_mesh()
+        .x_desc("Solucion aceptada")
+        .y_desc("Tasa de aceptacion")
+        .draw()?;
+
+    grafica_tasa.draw_series(LineSeries::new(
+        lotes.iter().map(|lote| (lote.aceptadas as f64, lote.tasa_aceptacion)),
+        &MAGENTA,
+    ))?;
+
+    raiz.present()?;
+    Ok(())
+}
+
+/// Funcion encargada de generar la grafica de convergencia de una ejecucion.
+///
+/// Recibe como parametros lo siguiente:
+/// - datos:`&[(f64, bool)]` : Muestra de la traza, peso de las soluciones aceptadas y si fue una mejora de la mejor solucion.
+/// - paso:usize : Cantidad de soluciones aceptadas que resume cada punto de la muestra.
+/// - lotes:`&[Lote]` : Informacion de cada lote calculado.
+/// - titulo:&str : Titulo de la grafica.
+/// - nombre:&str : Nombre de los archivos a generar dentro de la carpeta svgs.
+///
+/// Genera la misma grafica en `svgs/<nombre>.svg` y en `svgs/<nombre>.png`, de tal manera que pueda
+/// incluirse directamente en el documento de LaTeX.
+///
+/// # Example
+/// ```
+/// let (datos, paso) = tsp.tomar_traza().muestra(100_000)?;
+/// generar_convergencia(&datos, paso, &tsp.historial_lotes, "input-150 semilla 1", "tsp_semilla_1_convergencia")?;
+/// ```
+pub fn generar_convergencia(datos: &[(f64, bool)], paso: usize, lotes: &[Lote], titulo: &str, nombre: &str) -> Result<(), Box<dyn Error>> {
+    if datos.is_empty() {
+        println!("No hay datos para generar la grafica de convergencia.");
+        return Ok(());
+    }
+
+    let ruta_svg = format!("svgs/{}.svg", nombre);
+    dibujar(SVGBackend::new(&ruta_svg, (1600, 1000)).into_drawing_area(), datos, paso, lotes, titulo)?;
+
+    let ruta_png = format!("svgs/{}.png", nombre);
+    dibujar(BitMapBackend::new(&ruta_png, (1600, 1000)).into_drawing_area(), datos, paso, lotes, titulo)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_reducir() {
+        let puntos: Vec<usize> = (0..10).collect();
+        assert_eq!(reducir(&puntos, 20), puntos);
+        assert_eq!(reducir(&puntos, 5), vec![0, 2, 4, 6, 8, 9]);
+        assert_eq!(reducir(&puntos, 4), vec![0, 3, 6, 9]);
+        assert!(reducir::<usize>(&[], 4).is_empty());
+    }
+}
@@ -19,6 +19,7 @@ use rusqlite::{Connection, Result};
 /// }
 /// ```
 
+#[allow(clippy::empty_line_after_doc_comments)]
 struct Connections {
     id_city_1: i64,
     id_city_2: i64,
@@ -33,6 +34,8 @@ struct Connections {
 /// - coordenadas: Es un vector de tuplas, los cuales corresponden a las coordenadas de cada ciudad.
 /// - distancias_tsp: Es un vector encargado de almacenar todas las distancias que concuerdan con los indices de tsp
 /// - tsp: Es un vector encargado de almacer si la ciudad el indice i se encuentra en nuestro tsp
+/// - aristas: Es un vector que indica si la arista entre dos ciudades existe en la base de datos
+/// - ids: Es un vector con los identificadores de todas las ciudades de la base de datos
 ///
 /// # Example
 /// ```
@@ -41,6 +44,8 @@ struct Connections {
 ///            coordenadas: vec![(0.0,0.0); 1093] ,
 ///            distancias_tsp: Vec::new(),
 ///            tsp,
+///            aristas: vec![false; 1093*1093],
+///            ids: Vec::new(),
 ///        }
 /// ```
 ///
@@ -51,6 +56,8 @@ pub struct CityDB {
     pub coordenadas: Vec<(f64, f64)>,
     pub distancias_tsp: Vec<f64>,
     pub tsp: Vec<i32>,
+    pub aristas: Vec<bool>,
+    pub ids: Vec<i64>,
 }
 
 impl CityDB {
@@ -69,6 +76,7 @@ impl CityDB {
     /// ```
     /// let ciudad = CityDB::new(vectorTsp);
     /// ```
+    #[allow(clippy::ptr_arg, clippy::doc_lazy_continuation)]
     pub fn new(indices_tsp: &Vec<i64>) -> Self {
         let tsp = Self::cargar_tsp(indices_tsp);
         
@@ -77,6 +85,8 @@ impl CityDB {
             coordenadas: vec![(0.0,0.0); 1093] ,
             distancias_tsp: Vec::new(),
             tsp,
+            aristas: vec![false; 1093*1093],
+            ids: Vec::new(),
         }
     }
 
@@ -88,6 +98,7 @@ impl CityDB {
     /// Realiza un algoritmo similar a bucketSort de tal manera que dado el indice de la ciudad marcaremos
     /// la ciudad con un 1, de esta manera sabremos en el vector tsp que ciudades se encuentran en nuestro camino
     ///
+    #[allow(clippy::ptr_arg, clippy::needless_return)]
     fn cargar_tsp (indices_tsp: &Vec<i64>) -> Vec<i32> {
         let mut tsp = vec![0; 1093];
         for elemento in indices_tsp.iter() {
@@ -106,7 +117,7 @@ impl CityDB {
     /// 3. Para cada conexion leida, realizamos lo siguiente:
     ///    * Usaremos el polinomio de direccionamiento de tal manera que podamos simular una matriz. Es de esta manera que agregar la distancia de manera bidireccional a nuestro vector de adyacencias.
     ///    * Revisamos si ambas ciudades se encuentran en nuestro recorrido, en caso de que lo esten, agregamos la distancia a nuestro vector de distancias.
-    /// 4. Despues de esto, para cada ciudad obtendremos los valores correspondientes a su latitud y su longitud, los cuales serán almacenados como una tupla en nuestro vector de coordenadas.
+    /// 4. Despues de esto, para cada ciudad obtendremos los valores correspondientes a su latitud y su longitud, los cuales serán almacenados como una tupla en nuestro vector de coordenadas, y su identificador en el vector de ids.
     /// 5. Seguido a esto, ordenamos el vector de distancias-
     /// 6. Regresamos que el proceso se ha completado satisfactoriamente.
     ///
@@ -115,6 +126,7 @@ impl CityDB {
     /// let _ = ciudades.cargar_datos();
     ///```
     
+    #[allow(clippy::empty_line_after_doc_comments)]
     pub fn cargar_datos(&mut self) -> Result<()> {
         let conn = Connection::open("tsp.db")?;
 
@@ -131,6 +143,8 @@ impl CityDB {
             let c = connect?;
             self.data[(c.id_city_1*1093 + c.id_city_2) as usize] = c.distance;
             self.data[(c.id_city_2*1093 + c.id_city_1) as usize] = c.distance; // Es dirigida?
+            self.aristas[(c.id_city_1*1093 + c.id_city_2) as usize] = true;
+            self.aristas[(c.id_city_2*1093 + c.id_city_1) as usize] = true;
             if self.tsp[c.id_city_1 as usize] == 1 && self.tsp[c.id_city_2 as usize] == 1 {
                 self.distancias_tsp.push(c.distance);
             } 
@@ -144,6 +158,7 @@ impl CityDB {
         for city in cities_iter {
             let (id, lat, lon) = city?;
             self.coordenadas[id as usize] = (lat, lon);
+            self.ids.push(id);
         }
 
         self.distancias_tsp.sort_by(|a, b| a.partial_cmp(b).unwrap());
@@ -162,18 +177,33 @@ impl CityDB {
     /// let a = ciudades.get_latitude_longitude(1);
     /// ```
     
+    #[allow(clippy::empty_line_after_doc_comments, clippy::needless_return)]
     pub fn get_latitude_longitude(&mut self, u: i64) -> (f64, f64) {
         return self.coordenadas[u as usize];
     }
+
+    /// Revisar si existe la arista entre dos ciudades
+    ///
+    /// Funcion encargada de indicar si la arista entre dos ciudades se encuentra en la base de datos,
+    /// es decir, si su peso no es calculado con la distancia natural penalizada.
+    ///
+    /// # Example
+    /// ```
+    /// let existe = ciudades.existe_arista(1, 7);
+    /// ```
+    pub fn existe_arista(&self, u: i64, v: i64) -> bool {
+        self.aristas[(u*1093 + v) as usize]
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use rand::Rng;
-    use crate::fs;
+    use std::fs;
 
 
+    #[allow(clippy::needless_return, clippy::unnecessary_to_owned)]
     fn generar_numeros() -> Vec<i64>{
         let contenido = fs::read_to_string("inputs/input-40.tsp".to_string());
 
@@ -195,6 +225,7 @@ mod tests {
      }
 
     #[test]
+    #[allow(clippy::excessive_precision)]
     fn ok_cargar_datos() {
         let mut cities = CityDB::new(&generar_numeros());
         let _ = cities.cargar_datos();
@@ -210,10 +241,13 @@ mod tests {
         assert_eq!(cities.data[id1*1093 + id], -1.0);
         let id2 = 1085;
         assert_eq!(cities.data[id1*1093 + id2], 1347317.290000000037);
+        assert!(cities.existe_arista(id1 as i64, id2 as i64));
+        assert!(!cities.existe_arista(id1 as i64, id as i64));
         
     }
     
     #[test]
+    #[allow(clippy::excessive_precision)]
     fn ok_get_latitude_longitude(){
         let mut cities = CityDB::new(&generar_numeros());
         let _ = cities.cargar_datos();
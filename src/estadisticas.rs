@@ -0,0 +1,206 @@
+use crate::resultado::Resultado;
+use serde::Serialize;
+use std::fs::File;
+use std::io::Write;
+
+/// Estructura correspondiente a una cubeta del histograma.
+///
+/// - inicio:f64 : Limite inferior de la cubeta.
+/// - fin:f64 : Limite superior de la cubeta.
+/// - cantidad:usize : Cantidad de costos que caen dentro de la cubeta.
+///
+#[derive(Clone, Debug, Serialize)]
+pub struct Cubeta {
+    pub inicio: f64,
+    pub fin: f64,
+    pub cantidad: usize,
+}
+
+/// Estructura correspondiente al resumen de un intervalo de semillas.
+///
+/// Contiene las estadisticas de los costos finales de todas las semillas, asi como la mejor semilla
+/// con su recorrido y su longitud sin normalizar, los promedios de tiempo y soluciones aceptadas, el histograma de los costos,
+/// el normalizador de la instancia, con el que los costos se pueden convertir de regreso a longitudes, y el factor de penalizacion.
+///
+#[derive(Clone, Debug, Serialize)]
+pub struct Resumen {
+    pub ejecuciones: usize,
+    pub mejor: f64,
+    pub peor: f64,
+    pub promedio: f64,
+    pub mediana: f64,
+    pub desviacion_estandar: f64,
+    pub tiempo_promedio: f64,
+    pub aceptadas_promedio: f64,
+    pub mejor_semilla: i64,
+    pub mejor_solucion: Vec<i64>,
+    pub mejor_longitud: f64,
+    pub histograma: Vec<Cubeta>,
+    pub normalizador: f64,
+    pub factor_penalizacion: f64,
+}
+
+impl Resumen {
+
+    /// Constructor del resumen
+    ///
+    /// Recibe como parametros lo siguiente:
+    /// - resultados:`&[Resultado]` : Resultados de cada una de las semillas.
+    /// - num_cubetas:usize : Cantidad de cubetas del histograma.
+    ///
+    /// Regresa None en caso de que no existan resultados.
+    ///
+    /// # Example
+    /// ```
+    /// let resumen = Resumen::new(&resultados, 10);
+    /// ```
+    pub fn new(resultados: &[Resultado], num_cubetas: usize) -> Option<Self> {
+        let mejor = resultados.iter().min_by(|a, b| a.costo.total_cmp(&b.costo))?;
+        let n = resultados.len() as f64;
+
+        let mut costos: Vec<f64> = resultados.iter().map(|r| r.costo).collect();
+        costos.sort_by(|a, b| a.total_cmp(b));
+
+        let promedio = costos.iter().sum::<f64>() / n;
+        let varianza = costos.iter().map(|c| (c - promedio).powi(2)).sum::<f64>() / n;
+
+        Some(Resumen {
+            ejecuciones: resultados.len(),
+            mejor: costos[0],
+            peor: costos[costos.len() - 1],
+            promedio,
+            mediana: Self::mediana(&costos),
+            desviacion_estandar: varianza.sqrt(),
+            tiempo_promedio: resultados.iter().map(|r| r.tiempo).sum::<f64>() / n,
+            aceptadas_promedio: resultados.iter().map(|r| r.aceptadas as f64).sum::<f64>() / n,
+            mejor_semilla: mejor.semilla,
+            mejor_solucion: mejor.solucion.clone(),
+            mejor_longitud: mejor.longitud,
+            histograma: Self::histograma(&costos, num_cubetas),
+            normalizador: mejor.normalizador,
+            factor_penalizacion: mejor.factor_penalizacion,
+        })
+    }
+
+    /// Funcion encargada de obtener la mediana de una lista ordenada.
+    ///
+    fn mediana(ordenados: &[f64]) -> f64 {
+        let m = ordenados.len() / 2;
+        if ordenados.len().is_multiple_of(2) {
+            (ordenados[m - 1] + ordenados[m]) / 2.0
+        } else {
+            ordenados[m]
+        }
+    }
+
+    /// Funcion encargada de calcular el histograma de una lista ordenada.
+    ///
+    /// Divide el intervalo entre el menor y el mayor costo en cubetas del mismo ancho y cuenta
+    /// cuantos costos caen en cada una. Si todos los costos son iguales se genera una sola cubeta.
+    ///
+    fn histograma(ordenados: &[f64], num_cubetas: usize) -> Vec<Cubeta> {
+        let min = ordenados[0];
+        let max = ordenados[ordenados.len() - 1];
+        let num_cubetas = if max > min { num_cubetas.max(1) } else { 1 };
+        let ancho = (max - min) / num_cubetas as f64;
+
+        let mut cubetas: Vec<Cubeta> = (0..num_cubetas)
+            .map(|i| Cubeta {
+                inicio: min + ancho * i as f64,
+                fin: if i + 1 == num_cubetas { max } else { min + ancho * (i + 1) as f64 },
+                cantidad: 0,
+            })
+            .collect();
+
+        for costo in ordenados {
+            let i = if ancho > 0.0 { ((costo - min) / ancho) as usize } else { 0 };
+            cubetas[i.min(num_cubetas - 1)].cantidad += 1;
+        }
+        cubetas
+    }
+
+    /// Funcion encargada de imprimir el resumen en la salida estandar.
+    ///
+    /// Imprime las estadisticas de los costos y el histograma con barras de texto.
+    ///
+    pub fn imprimir(&self) {
+        println!("Ejecuciones: {}", self.ejecuciones);
+        println!("Mejor: {} Semilla {}", self.mejor, self.mejor_semilla);
+        println!("Mejor longitud: {} m ({:.3} km)", self.mejor_longitud, self.mejor_longitud / 1000.0);
+        println!("Normalizador: {} Factor de penalizacion: {}", self.normalizador, self.factor_penalizacion);
+        println!("Peor: {}", self.peor);
+        println!("Promedio: {} Mediana: {} Desviacion estandar: {}", self.promedio, self.mediana, self.desviacion_estandar);
+        println!("Tiempo promedio: {:.2}s Aceptadas promedio: {:.0}", self.tiempo_promedio, self.aceptadas_promedio);
+        let max_cantidad = self.histograma.iter().map(|c| c.cantidad).max().unwrap_or(0).max(1);
+        for cubeta in &self.histograma {
+            let barra = "#".repeat((cubeta.cantidad * 40).div_ceil(max_cantidad));
+            println!("[{:.6}, {:.6}] {:>5} {}", cubeta.inicio, cubeta.fin, cubeta.cantidad, barra);
+        }
+    }
+
+    /// Funcion encargada de escribir el resumen en un archivo json.
+    ///
+    /// # Example
+    /// ```
+    /// resumen.escribir_json("resultados/tsp_resumen.json")?;
+    /// ```
+    pub fn escribir_json(&self, ruta: &str) -> std::io::Result<()> {
+        let file = File::create(ruta)?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+}
+
+/// Funcion encargada de escribir los resultados de cada semilla en un archivo csv.
+///
+/// Cada renglon contiene la semilla, el costo, el tiempo, la cantidad de soluciones aceptadas y la longitud sin normalizar.
+///
+/// # Example
+/// ```
+/// escribir_csv(&resultados, "resultados/tsp_semillas.csv")?;
+/// ```
+pub fn escribir_csv(resultados: &[Resultado], ruta: &str) -> std::io::Result<()> {
+    let mut file = File::create(ruta)?;
+    writeln!(file, "semilla,costo,tiempo,aceptadas,longitud")?;
+    for r in resultados {
+        writeln!(file, "{},{},{},{},{}", r.semilla, r.costo, r.tiempo, r.aceptadas, r.longitud)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resultado(semilla: i64, costo: f64) -> Resultado {
+        Resultado { semilla, costo, tiempo: 1.0, aceptadas: 10, solucion: vec![semilla], longitud: costo * 1000.0, ..Resultado::default() }
+    }
+
+    #[test]
+    fn ok_resumen() {
+        let resultados = vec![resultado(1, 4.0), resultado(2, 1.0), resultado(3, 3.0), resultado(4, 2.0)];
+        let resumen = Resumen::new(&resultados, 3).unwrap();
+
+        assert_eq!(resumen.mejor, 1.0);
+        assert_eq!(resumen.peor, 4.0);
+        assert_eq!(resumen.promedio, 2.5);
+        assert_eq!(resumen.mediana, 2.5);
+        assert_eq!(resumen.desviacion_estandar, 1.25_f64.sqrt());
+        assert_eq!(resumen.mejor_semilla, 2);
+        assert_eq!(resumen.mejor_solucion, vec![2]);
+        assert_eq!(resumen.mejor_longitud, 1000.0);
+        assert_eq!(resumen.histograma.iter().map(|c| c.cantidad).sum::<usize>(), 4);
+        assert_eq!(resumen.histograma[2].cantidad, 2);
+    }
+
+    #[test]
+    fn ok_resumen_vacio_y_constante() {
+        assert!(Resumen::new(&[], 10).is_none());
+
+        let resumen = Resumen::new(&[resultado(1, 2.0), resultado(2, 2.0), resultado(3, 2.0)], 10).unwrap();
+        assert_eq!(resumen.mediana, 2.0);
+        assert_eq!(resumen.desviacion_estandar, 0.0);
+        assert_eq!(resumen.histograma.len(), 1);
+        assert_eq!(resumen.histograma[0].cantidad, 3);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::db::CityDB;
+use rand_chacha::ChaCha12Rng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::{Rng, SeedableRng};
+use std::f64::consts::PI;
+use std::fs;
+
+/// Estructura correspondiente a una region geografica rectangular.
+///
+/// - lat_min:f64 : Latitud minima.
+/// - lat_max:f64 : Latitud maxima.
+/// - lon_min:f64 : Longitud minima.
+/// - lon_max:f64 : Longitud maxima.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Caja {
+    pub lat_min: f64,
+    pub lat_max: f64,
+    pub lon_min: f64,
+    pub lon_max: f64,
+}
+
+impl Caja {
+
+    /// Funcion encargada de indicar si una latitud y longitud se encuentran dentro de la caja.
+    ///
+    pub fn contiene(&self, (lat, lon): (f64, f64)) -> bool {
+        lat >= self.lat_min && lat <= self.lat_max && lon >= self.lon_min && lon <= self.lon_max
+    }
+}
+
+/// Enumeracion correspondiente a la forma en la que se distribuyen las ciudades de una instancia sintetica.
+///
+/// - Uniforme : Las ciudades se distribuyen de manera uniforme en el cuadrado.
+/// - Agrupada : Las ciudades se distribuyen de manera normal alrededor de la cantidad dada de centros, distribuidos de manera uniforme.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Distribucion {
+    Uniforme,
+    Agrupada(usize),
+}
+
+/// Lado del cuadrado en el que se generan las instancias sinteticas.
+pub const LADO: f64 = 1000.0;
+
+/// Funcion encargada de elegir ciudades aleatorias de la base de datos.
+///
+/// Los parametros estan dados por:
+/// - db:&CityDB : Base de datos ya cargada.
+/// - n:usize : Cantidad de ciudades a elegir.
+/// - semilla:i64 : Semilla del generador de numeros pseudoaleatorios. Se usa `ChaCha12Rng`, cuya secuencia no cambia entre versiones de rand.
+/// - caja:`Option<Caja>` : Region a la que se restringen las ciudades, en caso de darse.
+/// - conexas:bool : Indica si las ciudades deben formar una grafica conexa con las aristas de la base de datos.
+///
+/// Si se piden ciudades conexas, se elige una ciudad inicial y se agregan ciudades vecinas de las ya elegidas,
+/// por lo que las ciudades forman parte de la misma componente conexa. Regresa `None` si no hay suficientes ciudades.
+///
+/// # Example
+/// ```
+/// let ciudades = ciudades_aleatorias(&db, 40, 7, None, true).expect("No hay suficientes ciudades");
+/// ```
+pub fn ciudades_aleatorias(db: &CityDB, n: usize, semilla: i64, caja: Option<Caja>, conexas: bool) -> Option<Vec<i64>> {
+    let mut rng = ChaCha12Rng::seed_from_u64(semilla as u64);
+    let candidatas: Vec<i64> = db.ids.iter().copied().filter(|c| caja.is_none_or(|caja| caja.contiene(db.coordenadas[*c as usize]))).collect();
+    if candidatas.len() < n {
+        return None;
+    }
+    if !conexas {
+        let mut ciudades: Vec<i64> = candidatas.choose_multiple(&mut rng, n).copied().collect();
+        ciudades.sort();
+        return Some(ciudades);
+    }
+
+    let mut orden = candidatas.clone();
+    orden.shuffle(&mut rng);
+    for inicial in orden {
+        let mut elegidas = vec![inicial];
+        let mut frontera: Vec<i64> = Vec::new();
+        let mut visitada = vec![false; db.coordenadas.len()];
+        visitada[inicial as usize] = true;
+        let mut actual = inicial;
+        while elegidas.len() < n {
+            for vecina in candidatas.iter().copied() {
+                if !visitada[vecina as usize] && db.existe_arista(actual, vecina) {
+                    visitada[vecina as usize] = true;
+                    frontera.push(vecina);
+                }
+            }
+            if frontera.is_empty() {
+                break;
+            }
+            actual = frontera.swap_remove(rng.random_range(0..frontera.len()));
+            elegidas.push(actual);
+        }
+        if elegidas.len() == n {
+            elegidas.sort();
+            return Some(elegidas);
+        }
+    }
+    None
+}
+
+/// Funcion encargada de generar las coordenadas de una instancia sintetica en el plano.
+///
+/// Los parametros estan dados por:
+/// - n:usize : Cantidad de ciudades.
+/// - distribucion:Distribucion : Distribucion de las ciudades.
+/// - semilla:i64 : Semilla del generador de numeros pseudoaleatorios.
+///
+/// Las coordenadas se encuentran dentro del cuadrado de lado `LADO`. En la distribucion agrupada la desviacion estandar de cada grupo es `LADO / 20`.
+///
+/// # Example
+/// ```
+/// let coordenadas = coordenadas_sinteticas(100, Distribucion::Agrupada(5), 7);
+/// ```
+pub fn coordenadas_sinteticas(n: usize, distribucion: Distribucion, semilla: i64) -> Vec<(f64, f64)> {
+    let mut rng = ChaCha12Rng::seed_from_u64(semilla as u64);
+    match distribucion {
+        Distribucion::Uniforme => (0..n).map(|_| (rng.random_range(0.0..LADO), rng.random_range(0.0..LADO))).collect(),
+        Distribucion::Agrupada(grupos) => {
+            let centros: Vec<(f64, f64)> = (0..grupos.max(1)).map(|_| (rng.random_range(0.0..LADO), rng.random_range(0.0..LADO))).collect();
+            let sigma = LADO / 20.0;
+            (0..n)
+                .map(|_| {
+                    let (cx, cy) = centros[rng.random_range(0..centros.len())];
+                    let (dx, dy) = normal(&mut rng);
+                    ((cx + sigma * dx).clamp(0.0, LADO), (cy + sigma * dy).clamp(0.0, LADO))
+                })
+                .collect()
+        }
+    }
+}
+
+/// Funcion encargada de obtener un par de valores con distribucion normal estandar con el metodo de Box-Muller.
+///
+fn normal(rng: &mut ChaCha12Rng) -> (f64, f64) {
+    let u1: f64 = 1.0 - rng.random::<f64>();
+    let u2: f64 = rng.random();
+    let r = (-2.0 * u1.ln()).sqrt();
+    (r * (2.0 * PI * u2).cos(), r * (2.0 * PI * u2).sin())
+}
+
+/// Funcion encargada de escribir una lista de ciudades separadas por comas, en el formato de los archivos de inputs.
+///
+/// # Example
+/// ```
+/// escribir_lista("inputs/input-aleatoria-40.tsp", &ciudades)?;
+/// ```
+pub fn escribir_lista(ruta: &str, ciudades: &[i64]) -> std::io::Result<()> {
+    fs::write(ruta, ciudades.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_coordenadas_sinteticas() {
+        let uniformes = coordenadas_sinteticas(50, Distribucion::Uniforme, 3);
+        assert_eq!(uniformes.len(), 50);
+        assert!(uniformes.iter().all(|(x, y)| (0.0..LADO).contains(x) && (0.0..LADO).contains(y)));
+        assert_eq!(uniformes, coordenadas_sinteticas(50, Distribucion::Uniforme, 3));
+        assert_ne!(uniformes, coordenadas_sinteticas(50, Distribucion::Uniforme, 4));
+        let agrupadas = coordenadas_sinteticas(50, Distribucion::Agrupada(3), 3);
+        assert!(agrupadas.iter().all(|(x, y)| (0.0..=LADO).contains(x) && (0.0..=LADO).contains(y)));
+    }
+
+    #[test]
+    fn ok_ciudades_aleatorias() {
+        let mut db = CityDB::new(&Vec::new());
+        db.ids = (1..=6).collect();
+        for c in 1..=6 {
+            db.coordenadas[c as usize] = (c as f64, 0.0);
+        }
+        for (u, v) in [(1, 2), (2, 3), (4, 5)] {
+            db.aristas[u * 1093 + v] = true;
+            db.aristas[v * 1093 + u] = true;
+        }
+        assert_eq!(ciudades_aleatorias(&db, 3, 1, None, true), Some(vec![1, 2, 3]));
+        assert_eq!(ciudades_aleatorias(&db, 4, 1, None, true), None);
+        assert_eq!(ciudades_aleatorias(&db, 4, 1, None, false).map(|c| c.len()), Some(4));
+        let caja = Caja { lat_min: 3.5, lat_max: 6.0, lon_min: -1.0, lon_max: 1.0 };
+        assert_eq!(ciudades_aleatorias(&db, 3, 1, Some(caja), false), Some(vec![4, 5, 6]));
+        assert_eq!(ciudades_aleatorias(&db, 2, 1, Some(caja), true), Some(vec![4, 5]));
+    }
+}
@@ -1,81 +1,208 @@
 use geo::{LineString, Point};
 use geo::algorithm::simplify::Simplify;
-use svg::node::element::{Circle, Polyline, Line, Text, Rectangle};
+use svg::node::element::{Circle, Group, Polyline, Line, Text, Rectangle};
 use svg::Document;
 
+/// Estructura correspondiente a las opciones de la grafica svg de soluciones aceptadas.
+///
+/// - width:f64 : Ancho del svg.
+/// - height:f64 : Alto del svg.
+/// - margin:f64 : Margen alrededor de la grafica.
+/// - tolerancia:f64 : Tolerancia en pixeles de la simplificacion RDP de la linea.
+/// - max_puntos:`Option<usize>` : Cantidad maxima de puntos de la linea, en caso de exceder se aumenta la tolerancia.
+/// - color_linea:String : Color de la linea de soluciones aceptadas.
+/// - color_mejora:String : Color de los puntos de mejora de la mejor solucion.
+/// - color_fondo:String : Color del fondo.
+/// - titulo:`Option<String>` : Titulo de la grafica, por ejemplo con la instancia y la semilla.
+/// - escala_log:bool : Indica si el eje y se dibuja en escala logaritmica.
+/// - desde:`Option<usize>` : Primer solucion aceptada a graficar.
+/// - hasta:`Option<usize>` : Ultima solucion aceptada a graficar.
+/// - acercamiento:`Option<f64>` : Porcentaje final de la ventana que se muestra acercado en un recuadro.
+///
+#[derive(Clone, Debug)]
+pub struct OpcionesSvg {
+    pub width: f64,
+    pub height: f64,
+    pub margin: f64,
+    pub tolerancia: f64,
+    pub max_puntos: Option<usize>,
+    pub color_linea: String,
+    pub color_mejora: String,
+    pub color_fondo: String,
+    pub titulo: Option<String>,
+    pub escala_log: bool,
+    pub desde: Option<usize>,
+    pub hasta: Option<usize>,
+    pub acercamiento: Option<f64>,
+}
 
-pub fn generar(datos: Vec<f64>, datos_puntos: Vec<(f64, bool)> ,nombre: String) {
-    let ruta = format!("svgs/{}.svg", nombre);
-    if datos.is_empty(){
-        println!("No hay datos para generar el SVG.");
-        return;
+impl Default for OpcionesSvg {
+    fn default() -> Self {
+        OpcionesSvg {
+            width: 2800.0,
+            height: 1600.0,
+            margin: 120.0,
+            tolerancia: 1.0,
+            max_puntos: None,
+            color_linea: "blue".to_string(),
+            color_mejora: "red".to_string(),
+            color_fondo: "white".to_string(),
+            titulo: None,
+            escala_log: false,
+            desde: None,
+            hasta: None,
+            acercamiento: None,
+        }
     }
+}
 
-    let puntos: Vec<Point<f64>> = datos
-        .iter()
-        .enumerate()
-        .map(|(i, valor)| Point::new(i as f64, *valor))
-        .collect();
-
-    let linea_original = LineString::from(puntos.clone());
-    
-    let puntos_importantes: Vec<[f64; 2]> = datos_puntos
-        .iter()
-        .enumerate()
-        .filter(|(_, (_, es_mejora))| *es_mejora)
-        .map(|(i, (valor, _))| [i as f64, *valor])
-        .collect();
+/// Funcion encargada de simplificar una linea dada en coordenadas del svg.
+///
+/// Aplica RDP con la tolerancia dada en pixeles, de esta manera la simplificacion no depende de la escala
+/// de los datos de cada instancia. Si se da una cantidad maxima de puntos y la linea simplificada la excede,
+/// la tolerancia se duplica hasta cumplirla.
+///
+fn simplificar(puntos: Vec<Point<f64>>, tolerancia: f64, max_puntos: Option<usize>) -> Vec<Point<f64>> {
+    let linea = LineString::from(puntos);
+    let mut tolerancia = tolerancia.max(0.0);
+    let mut simplificada = linea.simplify(tolerancia);
+    if let Some(max) = max_puntos {
+        let max = max.max(2);
+        while simplificada.0.len() > max {
+            tolerancia = if tolerancia > 0.0 { tolerancia * 2.0 } else { 0.1 };
+            simplificada = linea.simplify(tolerancia);
+        }
+    }
+    simplificada.into_points()
+}
 
-    let epsilon = 2.0;
-    let linea_rdp = linea_original.simplify(epsilon);
+/// Estructura correspondiente a un area rectangular del svg en la que se dibuja una serie.
+///
+struct Area {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
 
-    let width = 2800.0;
-    let height = 1600.0;
-    let margin = 120.0;
+/// Funcion encargada de transformar un peso al valor del eje y.
+///
+/// En escala logaritmica se usa el logaritmo base 10 del peso, los pesos no positivos se acotan
+/// al menor valor positivo para no obtener valores infinitos.
+///
+fn valor_y(valor: f64, escala_log: bool) -> f64 {
+    if escala_log {
+        valor.max(f64::MIN_POSITIVE).log10()
+    } else {
+        valor
+    }
+}
 
-    let (min_y, max_y) = datos.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), valor| {
-        (min.min(*valor), max.max(*valor))
+/// Funcion encargada de dibujar una serie de soluciones aceptadas dentro de un area.
+///
+/// Cada punto de la serie es (numero de solucion aceptada, peso, es mejora). Los ejes x y y se ajustan
+/// al rango de la serie, la linea se simplifica en coordenadas del svg y las mejoras se dibujan como circulos.
+/// Regresa el grupo con la linea, los circulos y las etiquetas del rango de cada eje.
+///
+fn serie(puntos: &[(usize, f64, bool)], area: &Area, opciones: &OpcionesSvg) -> Group {
+    let (min_y, max_y) = puntos.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), (_, valor, _)| {
+        let y = valor_y(*valor, opciones.escala_log);
+        (min.min(y), max.max(y))
     });
+    let rango_y = (max_y - min_y).max(f64::EPSILON);
+    let min_x = puntos.first().map(|p| p.0).unwrap_or(0) as f64;
+    let rango_x = (puntos.last().map(|p| p.0).unwrap_or(0) as f64 - min_x).max(1.0);
 
-    let max_x = (datos.len() - 1) as f64;
-
-    let map_svg = |x:f64, y:f64| -> (f64, f64) {
-        let svg_x = (x / max_x) * (width - 2.0 * margin) + margin;
-        let svg_y = (1.0 - (y- min_y) / (max_y - min_y)) * (height - 2.0 * margin) + margin;
+    let map_svg = |x: usize, valor: f64| -> (f64, f64) {
+        let svg_x = ((x as f64 - min_x) / rango_x) * area.width + area.x;
+        let svg_y = (1.0 - (valor_y(valor, opciones.escala_log) - min_y) / rango_y) * area.height + area.y;
         (svg_x, svg_y)
     };
 
-    let point_string = linea_rdp
-        .points()
-        .map(|p| {
-            let (svg_x, svg_y) = map_svg(p.x(),p.y());
-            format!("{:.2},{:.2}", svg_x, svg_y)
-        })
+    let linea_svg: Vec<Point<f64>> = puntos.iter().map(|(i, valor, _)| Point::from(map_svg(*i, *valor))).collect();
+    let point_string = simplificar(linea_svg, opciones.tolerancia, opciones.max_puntos)
+        .iter()
+        .map(|p| format!("{:.2},{:.2}", p.x(), p.y()))
         .collect::<Vec<String>>()
         .join(" ");
 
-    let linea = Polyline::new()
-        .set("fill", "none")
-        .set("stroke", "blue")
-        .set("stoke-width", 2)
-        .set("points", point_string);
+    let mut grupo = Group::new().add(
+        Polyline::new()
+            .set("fill", "none")
+            .set("stroke", opciones.color_linea.as_str())
+            .set("stroke-width", 2)
+            .set("points", point_string),
+    );
 
-    let puntos_mejora = puntos_importantes
-        .iter().map(|&[x,y]| {
-            let (svg_x, svg_y) = map_svg(x,y);
+    for (i, valor, _) in puntos.iter().filter(|(_, _, es_mejora)| *es_mejora) {
+        let (svg_x, svg_y) = map_svg(*i, *valor);
+        grupo = grupo.add(
             Circle::new()
                 .set("cx", ((svg_x * 100.0).round())/100.0)
                 .set("cy", ((svg_y * 100.0).round())/100.0)
                 .set("r", 3)
-                .set("fill", "red")
-        });
-    
+                .set("fill", opciones.color_mejora.as_str()),
+        );
+    }
+
+    let etiqueta = |texto: String, x: f64, y: f64, ancla: &str| {
+        Text::new(texto)
+            .set("x", x)
+            .set("y", y)
+            .set("text-anchor", ancla)
+            .set("font-size", 14)
+            .set("fill", "black")
+    };
+    let desescalar = |y: f64| if opciones.escala_log { 10f64.powf(y) } else { y };
+    grupo
+        .add(etiqueta(format!("{:.4}", desescalar(max_y)), area.x - 6.0, area.y + 5.0, "end"))
+        .add(etiqueta(format!("{:.4}", desescalar(min_y)), area.x - 6.0, area.y + area.height, "end"))
+        .add(etiqueta(format!("{}", min_x), area.x, area.y + area.height + 18.0, "start"))
+        .add(etiqueta(format!("{}", min_x + rango_x), area.x + area.width, area.y + area.height + 18.0, "end"))
+}
+
+/// Funcion encargada de generar la grafica svg de las soluciones aceptadas.
+///
+/// Recibe como parametros lo siguiente:
+/// - datos:`&[(f64, bool)]` : Muestra de la traza, peso de las soluciones aceptadas y si fue una mejora de la mejor solucion.
+/// - paso:usize : Cantidad de soluciones aceptadas que resume cada punto de la muestra.
+/// - nombre:String : Nombre del svg a generar dentro de la carpeta svgs.
+/// - opciones:&OpcionesSvg : Dimensiones, colores, titulo, simplificacion, escala y ventana de la grafica.
+///
+/// Si se solicita un acercamiento, se dibuja en la esquina superior derecha un recuadro con el porcentaje
+/// final de la ventana, con su propio rango en el eje y.
+///
+/// # Example
+/// ```
+/// let (datos, paso) = tsp.tomar_traza().muestra(100_000)?;
+/// generar(&datos, paso, "tsp_semilla_1".to_string(), &OpcionesSvg::default());
+/// ```
+pub fn generar(datos: &[(f64, bool)], paso: usize, nombre: String, opciones: &OpcionesSvg) {
+    let ruta = format!("svgs/{}.svg", nombre);
+    let paso = paso.max(1);
+    let desde = (opciones.desde.unwrap_or(0) / paso).min(datos.len());
+    let hasta = opciones.hasta.map(|m| m / paso + 1).unwrap_or(datos.len()).clamp(desde, datos.len());
+    let puntos: Vec<(usize, f64, bool)> = (desde..hasta)
+        .map(|i| (i * paso, datos[i].0, datos[i].1))
+        .collect();
+    if puntos.is_empty(){
+        println!("No hay datos para generar el SVG.");
+        return;
+    }
+
+    let width = opciones.width;
+    let height = opciones.height;
+    let margin = opciones.margin;
+
+    let area = Area { x: margin, y: margin, width: width - 2.0 * margin, height: height - 2.0 * margin };
+    let linea = serie(&puntos, &area, opciones);
+
     let fondo = Rectangle::new()
         .set("x", 0)
         .set("y", 0)
         .set("width", width)
         .set("height", height)
-        .set("fill", "white");
+        .set("fill", opciones.color_fondo.as_str());
 
     let eje_x = Line::new()
         .set("x1", margin)
@@ -101,7 +228,8 @@ pub fn generar(datos: Vec<f64>, datos_puntos: Vec<(f64, bool)> ,nombre: String)
         .set("fill", "black")
         .add(svg::node::Text::new(""));
 
-    let etiqueta_y = Text::new("Peso Solucion")
+    let texto_y = if opciones.escala_log { "Peso Solucion (escala logaritmica)" } else { "Peso Solucion" };
+    let etiqueta_y = Text::new(texto_y)
         .set("x", 20.0)
         .set("y", height / 2.0)
         .set("transform", format!("rotate(-90, {}, {})", 20.0, height / 2.0))
@@ -109,7 +237,7 @@ pub fn generar(datos: Vec<f64>, datos_puntos: Vec<(f64, bool)> ,nombre: String)
         .set("font-size", 20)
         .set("fill", "black")
         .add(svg::node::Text::new(""));
-    
+
     let mut file = Document::new().set("viewBox", (0,0,width, height))
         .add(fondo)
         .add(eje_x)
@@ -117,14 +245,69 @@ pub fn generar(datos: Vec<f64>, datos_puntos: Vec<(f64, bool)> ,nombre: String)
         .add(etiqueta_x)
         .add(etiqueta_y)
         .add(linea);
-    
-    for p in puntos_mejora {
-        file = file.add(p);
+
+    if let Some(titulo) = &opciones.titulo {
+        file = file.add(
+            Text::new(titulo.as_str())
+                .set("x", width / 2.0)
+                .set("y", margin / 2.0)
+                .set("text-anchor", "middle")
+                .set("font-size", 28)
+                .set("fill", "black"),
+        );
+    }
+
+    if let Some(porcentaje) = opciones.acercamiento {
+        let cantidad = ((puntos.len() as f64 * porcentaje.clamp(0.0, 100.0) / 100.0).ceil() as usize).clamp(2.min(puntos.len()), puntos.len());
+        let recuadro = Area {
+            x: width - margin - area.width * 0.4,
+            y: margin + 20.0,
+            width: area.width * 0.4 - 20.0,
+            height: area.height * 0.4,
+        };
+        file = file
+            .add(
+                Rectangle::new()
+                    .set("x", recuadro.x - 90.0)
+                    .set("y", recuadro.y - 30.0)
+                    .set("width", recuadro.width + 110.0)
+                    .set("height", recuadro.height + 60.0)
+                    .set("fill", opciones.color_fondo.as_str())
+                    .set("stroke", "black")
+                    .set("stroke-width", 1),
+            )
+            .add(
+                Text::new(format!("Ultimo {}%", porcentaje))
+                    .set("x", recuadro.x + recuadro.width / 2.0)
+                    .set("y", recuadro.y - 10.0)
+                    .set("text-anchor", "middle")
+                    .set("font-size", 16)
+                    .set("fill", "black"),
+            )
+            .add(serie(&puntos[puntos.len() - cantidad..], &recuadro, opciones));
     }
 
-    
-    
     svg::save(ruta, &file).expect("No se pudo guardar el SVG");
-    
+
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_simplificar() {
+        let puntos: Vec<Point<f64>> = (0..100).map(|i| Point::new(i as f64, if i % 2 == 0 { 0.0 } else { 0.5 })).collect();
+        assert_eq!(simplificar(puntos.clone(), 1.0, None).len(), 2);
+        assert_eq!(simplificar(puntos.clone(), 0.1, None).len(), 100);
+        assert!(simplificar(puntos.clone(), 0.1, Some(10)).len() <= 10);
+        assert_eq!(simplificar(puntos, 0.0, Some(1)).len(), 2);
+    }
+
+    #[test]
+    fn ok_valor_y() {
+        assert_eq!(valor_y(100.0, false), 100.0);
+        assert_eq!(valor_y(100.0, true), 2.0);
+        assert!(valor_y(0.0, true).is_finite());
+    }
+}
@@ -43,38 +43,7 @@ impl Grafica {
     /// ```
     ///
     pub fn distancia_natural(&mut self, u: i64, v: i64) -> f64{
-        let r = 6373000.0;
-        let c = 2.0 * self.get_a(u, v).sqrt().atan2((1.0 - self.get_a(u, v)).sqrt());
-        return r * c;
-    }
-
-    /// Funcion Privada encargada de encapsular algunas de las operaciones que permiten calcular la distancia natural entre dos ciudades.
-    ///
-    /// Como parametros tenemos:
-    /// - u:i64 : Corresponde al indice de la primer ciudad.
-    /// - v:i64 : Corresponde al indice de la segunda ciudad.
-    ///
-    /// La funcion se encarga de obtener las latitudes y longitudes correspondientes a cada una de las ciudades.
-    /// Posteriormente utiliza estas para pasarlas a radianes y realizar el calculo auxiliar para obtener la distancia natural.
-    ///
-    fn get_a (&mut self, u: i64, v: i64) -> f64 {
-        let u_tupla = self.db.get_latitude_longitude(u);
-        let v_tupla = self.db.get_latitude_longitude(v);
-
-        let rad = PI/180.0;
-        
-        let u_latitude_radianes = u_tupla.0 * rad;
-        let u_longitude_radianes = u_tupla.1 * rad;
-
-        let v_latitude_radianes = v_tupla.0 * rad;
-        let v_longitude_radianes = v_tupla.1 * rad;
-        
-        
-        let a = (((v_latitude_radianes - u_latitude_radianes)/2.0).sin()).powf(2.0);
-
-        let b = (((v_longitude_radianes - u_longitude_radianes)/2.0).sin()).powf(2.0);
-
-        return a + (u_latitude_radianes.cos() * v_latitude_radianes.cos() * b);
+        distancia_natural(self.db.get_latitude_longitude(u), self.db.get_latitude_longitude(v))
     }
 
     
@@ -87,6 +56,7 @@ impl Grafica {
     /// ```
     /// let p:f64 = grafica.peso(0,1);
     /// ```
+    #[allow(clippy::needless_return)]
     pub fn peso(&mut self, u: i64, v: i64) -> f64 {
         if self.db.data[(u*1093 +  v) as usize] == -1.0 {
             self.db.data[(u*1093 + v) as usize] = self.distancia_natural(u, v) * self.db.distancias_tsp[self.db.distancias_tsp.len()-1];
@@ -97,11 +67,59 @@ impl Grafica {
 
 }
 
+/// Funcion encargada de calcular la distancia natural en metros entre dos coordenadas.
+///
+/// Como parametros tenemos:
+/// - u:(f64, f64) : Corresponde a la latitud y longitud de la primer ciudad.
+/// - v:(f64, f64) : Corresponde a la latitud y longitud de la segunda ciudad.
+///
+/// Pasa las latitudes y longitudes a radianes para calcular la distancia con la formula del haversine.
+///
+/// # Example
+/// ```
+/// let a:f64 = distancia_natural((19.43, -99.13), (20.67, -103.35));
+/// ```
+///
+#[allow(clippy::needless_return)]
+pub fn distancia_natural(u: (f64, f64), v: (f64, f64)) -> f64 {
+    let r = 6373000.0;
+    let a = get_a(u, v);
+    let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
+    return r * c;
+}
+
+/// Funcion Privada encargada de encapsular algunas de las operaciones que permiten calcular la distancia natural entre dos ciudades.
+///
+/// Como parametros tenemos:
+/// - u_tupla:(f64, f64) : Corresponde a la latitud y longitud de la primer ciudad.
+/// - v_tupla:(f64, f64) : Corresponde a la latitud y longitud de la segunda ciudad.
+///
+/// La funcion pasa las latitudes y longitudes a radianes y realiza el calculo auxiliar para obtener la distancia natural.
+///
+#[allow(clippy::needless_return)]
+fn get_a(u_tupla: (f64, f64), v_tupla: (f64, f64)) -> f64 {
+    let rad = PI/180.0;
+    
+    let u_latitude_radianes = u_tupla.0 * rad;
+    let u_longitude_radianes = u_tupla.1 * rad;
+
+    let v_latitude_radianes = v_tupla.0 * rad;
+    let v_longitude_radianes = v_tupla.1 * rad;
+    
+    
+    let a = (((v_latitude_radianes - u_latitude_radianes)/2.0).sin()).powf(2.0);
+
+    let b = (((v_longitude_radianes - u_longitude_radianes)/2.0).sin()).powf(2.0);
+
+    return a + (u_latitude_radianes.cos() * v_latitude_radianes.cos() * b);
+}
+
 #[cfg(test)]
 mod tests{
     use super::*;
-    use crate::fs;
+    use std::fs;
     
+    #[allow(clippy::needless_return, clippy::unnecessary_to_owned)]
     fn generar_numeros() -> Vec<i64>{
 
         let contenido = fs::read_to_string("inputs/input-40.tsp".to_string());
@@ -126,6 +144,7 @@ mod tests{
     }
 
     #[test]
+    #[allow(clippy::excessive_precision)]
     fn ok_peso() {
         let mut cities = CityDB::new(&generar_numeros());
         let _ = cities.cargar_datos();
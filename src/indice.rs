@@ -0,0 +1,108 @@
+use crate::resultado::Resultado;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// Estructura correspondiente al indice de resultados.
+///
+/// El indice es un archivo csv en el que cada renglon registra una semilla completada:
+/// el hash de la instancia, el hash de los parametros, la semilla, el costo y la ruta del json con el resultado.
+/// Permite reanudar un intervalo de semillas omitiendo las que ya fueron calculadas.
+///
+/// - ruta:String : Ruta del archivo del indice.
+/// - entradas: Mapa de (hash instancia, hash parametros, semilla) a la ruta del resultado.
+///
+pub struct Indice {
+    ruta: String,
+    entradas: HashMap<(String, String, i64), String>,
+}
+
+impl Indice {
+
+    /// Funcion encargada de cargar el indice.
+    ///
+    /// Si el archivo no existe se regresa un indice vacio, el cual sera creado al registrar la primer semilla.
+    ///
+    /// # Example
+    /// ```
+    /// let indice = Indice::cargar("resultados/indice.csv")?;
+    /// ```
+    pub fn cargar(ruta: &str) -> std::io::Result<Self> {
+        let mut entradas = HashMap::new();
+        if Path::new(ruta).exists() {
+            let contenido = fs::read_to_string(ruta)?;
+            for linea in contenido.lines().skip(1) {
+                let campos: Vec<&str> = linea.splitn(5, ',').collect();
+                if campos.len() < 5 {
+                    continue;
+                }
+                if let Ok(semilla) = campos[2].parse::<i64>() {
+                    entradas.insert((campos[0].to_string(), campos[1].to_string(), semilla), campos[4].to_string());
+                }
+            }
+        }
+        Ok(Indice { ruta: ruta.to_string(), entradas })
+    }
+
+    /// Funcion encargada de buscar una semilla completada.
+    ///
+    /// Regresa la ruta del resultado en caso de que la semilla se haya completado con la misma instancia y parametros.
+    ///
+    pub fn buscar(&self, hash_instancia: &str, hash_parametros: &str, semilla: i64) -> Option<&str> {
+        self.entradas
+            .get(&(hash_instancia.to_string(), hash_parametros.to_string(), semilla))
+            .map(|ruta| ruta.as_str())
+    }
+
+    /// Funcion encargada de registrar una semilla completada.
+    ///
+    /// Agrega el renglon al final del archivo, de esta forma lo registrado se conserva aunque se interrumpa la ejecucion.
+    ///
+    /// # Example
+    /// ```
+    /// indice.registrar(&resultado, "resultados/tsp_semilla_1.json")?;
+    /// ```
+    pub fn registrar(&mut self, resultado: &Resultado, ruta_resultado: &str) -> std::io::Result<()> {
+        let nuevo = !Path::new(&self.ruta).exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.ruta)?;
+        if nuevo {
+            writeln!(file, "hash_instancia,hash_parametros,semilla,costo,ruta")?;
+        }
+        writeln!(file, "{},{},{},{},{}", resultado.hash_instancia, resultado.hash_parametros, resultado.semilla, resultado.costo, ruta_resultado)?;
+        self.entradas.insert(
+            (resultado.hash_instancia.clone(), resultado.hash_parametros.clone(), resultado.semilla),
+            ruta_resultado.to_string(),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_registrar_y_cargar() {
+        let ruta = std::env::temp_dir().join(format!("indice_prueba_{}.csv", std::process::id()));
+        let ruta = ruta.to_str().unwrap();
+        let _ = fs::remove_file(ruta);
+
+        let resultado = Resultado {
+            hash_instancia: "a".to_string(),
+            hash_parametros: "b".to_string(),
+            semilla: 3,
+            ..Resultado::default()
+        };
+        let mut indice = Indice::cargar(ruta).unwrap();
+        assert_eq!(indice.buscar("a", "b", 3), None);
+        indice.registrar(&resultado, "resultados/x.json").unwrap();
+        assert_eq!(indice.buscar("a", "b", 3), Some("resultados/x.json"));
+
+        let indice = Indice::cargar(ruta).unwrap();
+        assert_eq!(indice.buscar("a", "b", 3), Some("resultados/x.json"));
+        assert_eq!(indice.buscar("a", "c", 3), None);
+        assert_eq!(indice.buscar("a", "b", 4), None);
+        fs::remove_file(ruta).unwrap();
+    }
+}
@@ -0,0 +1,699 @@
+use crate::db::CityDB;
+use crate::grafica::{distancia_natural, Grafica};
+use crate::resultado::{fnv, hash_instancia};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Read, Write};
+use std::path::Path;
+
+const MAGICO: &[u8; 4] = b"INS2";
+/// Identificador maximo de una ciudad leida de un archivo, pues la instancia reserva una entrada por cada identificador hasta el mayor.
+const MAXIMO_ID: i64 = 1_000_000;
+
+/// Enumeracion correspondiente a la forma de calcular la distancia entre dos coordenadas.
+///
+/// - Euclidiana : Distancia euclidiana entre puntos del plano.
+/// - Geografica : Distancia natural en metros entre una latitud y longitud, la misma con la que se penalizan las aristas de la base de datos.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Metrica {
+    Euclidiana,
+    Geografica,
+}
+
+impl Metrica {
+
+    /// Funcion encargada de calcular la distancia entre dos coordenadas con la metrica.
+    ///
+    pub fn distancia(&self, u: (f64, f64), v: (f64, f64)) -> f64 {
+        match self {
+            Metrica::Euclidiana => (u.0 - v.0).hypot(u.1 - v.1),
+            Metrica::Geografica => distancia_natural(u, v),
+        }
+    }
+}
+
+/// Estructura correspondiente a una instancia del TSP.
+///
+/// Almacena todo lo que el recocido necesita de la instancia, sin depender de la base de datos:
+/// - nombre:String : Nombre de la instancia, por lo general el archivo del que se leyo.
+/// - ciudades:`Vec<i64>` : Identificadores de las ciudades, en el orden en el que se dieron.
+/// - coordenadas:`Vec<(f64, f64)>` : Coordenadas de cada ciudad, en el mismo orden que las ciudades.
+/// - hash:String : Hash de la instancia, permite identificarla aunque cambie el nombre.
+/// - normalizador:f64 : Valor entre el que se divide la longitud de un recorrido para obtener su costo, se calcula una sola vez al construir la instancia.
+/// - distancia_maxima:f64 : Mayor distancia entre las aristas existentes, con ella se penalizan las aristas que no existen.
+/// - posiciones:`Vec<usize>` : Posicion de cada ciudad dentro de la lista de ciudades, indexada por identificador.
+/// - distancias:`Vec<f64>` : Matriz de distancias entre cada par de ciudades, indexada por posicion.
+/// - aristas:`Vec<bool>` : Matriz que indica si la arista entre cada par de ciudades existe.
+///
+/// Los identificadores de las ciudades deben ser enteros no negativos, ya que se usan como indices.
+///
+/// # Example
+/// ```
+/// let instancia = Instancia::desde_coordenadas("prueba", vec![1, 2, 3], vec![(0.0, 0.0), (3.0, 4.0), (6.0, 8.0)], Metrica::Euclidiana);
+/// assert_eq!(instancia.peso(1, 2), 5.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instancia {
+    pub nombre: String,
+    pub ciudades: Vec<i64>,
+    pub coordenadas: Vec<(f64, f64)>,
+    pub hash: String,
+    pub normalizador: f64,
+    pub distancia_maxima: f64,
+    posiciones: Vec<usize>,
+    distancias: Vec<f64>,
+    aristas: Vec<bool>,
+}
+
+impl Instancia {
+
+    /// Constructor de la instancia a partir de su matriz de distancias.
+    ///
+    /// Los parametros estan dados por:
+    /// - nombre:&str : Nombre de la instancia.
+    /// - ciudades:`Vec<i64>` : Identificadores de las ciudades.
+    /// - coordenadas:`Vec<(f64, f64)>` : Coordenadas de cada ciudad, unicamente se usan para los mapas.
+    /// - distancias:`Vec<f64>` : Matriz de n por n con la distancia entre cada par de ciudades, indexada por posicion.
+    /// - aristas:`Vec<bool>` : Matriz de n por n que indica que aristas existen, el resto se consideran penalizadas.
+    ///
+    /// El normalizador y la distancia maxima se calculan a partir de las aristas existentes.
+    ///
+    pub fn new(nombre: &str, ciudades: Vec<i64>, coordenadas: Vec<(f64, f64)>, distancias: Vec<f64>, aristas: Vec<bool>) -> Self {
+        let n = ciudades.len();
+        let mut existentes = Vec::new();
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if aristas[i * n + j] {
+                    existentes.push(distancias[i * n + j]);
+                }
+            }
+        }
+        existentes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let bytes = ciudades.iter().flat_map(|c| c.to_le_bytes()).chain(distancias.iter().flat_map(|d| d.to_le_bytes()));
+        let hash = fnv(bytes);
+        Self::construir(nombre, ciudades, coordenadas, distancias, aristas, &existentes, hash)
+    }
+
+    /// Constructor de la instancia a partir de coordenadas.
+    ///
+    /// Todas las aristas existen y su distancia se calcula con la metrica dada.
+    ///
+    /// # Example
+    /// ```
+    /// let instancia = Instancia::desde_coordenadas("prueba", vec![1, 2], vec![(19.43, -99.13), (20.67, -103.35)], Metrica::Geografica);
+    /// ```
+    pub fn desde_coordenadas(nombre: &str, ciudades: Vec<i64>, coordenadas: Vec<(f64, f64)>, metrica: Metrica) -> Self {
+        let n = ciudades.len();
+        let mut distancias = vec![0.0; n * n];
+        for i in 0..n {
+            for j in 0..n {
+                distancias[i * n + j] = metrica.distancia(coordenadas[i], coordenadas[j]);
+            }
+        }
+        let mut aristas = vec![true; n * n];
+        for i in 0..n {
+            aristas[i * n + i] = false;
+        }
+        Self::new(nombre, ciudades, coordenadas, distancias, aristas)
+    }
+
+    /// Constructor de la instancia a partir de la base de datos ya cargada.
+    ///
+    /// Las distancias son las de la base de datos, y las aristas que no existen se penalizan con la distancia natural
+    /// multiplicada por la distancia maxima, de la misma forma que `Grafica::peso`. Si no existe ninguna arista entre las ciudades
+    /// la distancia maxima es 1, por lo que las aristas penalizadas pesan su distancia natural. El hash es el mismo que el de la lista de ciudades.
+    ///
+    /// # Example
+    /// ```
+    /// let mut cities = CityDB::new(&ciudades);
+    /// cities.cargar_datos()?;
+    /// let instancia = Instancia::desde_db(cities, "inputs/input-40.tsp", &ciudades);
+    /// ```
+    pub fn desde_db(db: CityDB, nombre: &str, ciudades: &[i64]) -> Self {
+        let n = ciudades.len();
+        let coordenadas = ciudades.iter().map(|c| db.coordenadas[*c as usize]).collect();
+        let mut aristas = vec![false; n * n];
+        let mut distancias = vec![0.0; n * n];
+        let existentes = db.distancias_tsp.clone();
+        let maxima = distancia_maxima(&existentes);
+        let mut grafica = Grafica::new(db);
+        for (i, u) in ciudades.iter().enumerate() {
+            for (j, v) in ciudades.iter().enumerate() {
+                aristas[i * n + j] = grafica.db.existe_arista(*u, *v);
+                distancias[i * n + j] = if aristas[i * n + j] { grafica.peso(*u, *v) } else { grafica.distancia_natural(*u, *v) * maxima };
+            }
+        }
+        Self::construir(nombre, ciudades.to_vec(), coordenadas, distancias, aristas, &existentes, hash_instancia(ciudades))
+    }
+
+    /// Constructor de la instancia a partir de la base de datos `tsp.db`.
+    ///
+    /// # Example
+    /// ```
+    /// let instancia = Instancia::cargar_db("inputs/input-40.tsp", &ciudades)?;
+    /// ```
+    pub fn cargar_db(nombre: &str, ciudades: &[i64]) -> rusqlite::Result<Self> {
+        let mut cities = CityDB::new(&ciudades.to_vec());
+        cities.cargar_datos()?;
+        Ok(Self::desde_db(cities, nombre, ciudades))
+    }
+
+    /// Constructor de la instancia a partir de un archivo TSPLIB.
+    ///
+    /// Se leen las coordenadas de la seccion `NODE_COORD_SECTION`. Con `EDGE_WEIGHT_TYPE` igual a `EUC_2D` se usa la distancia euclidiana,
+    /// y con `GEO` las coordenadas en grados y minutos se pasan a grados y se usa la distancia geografica. Las distancias no se redondean.
+    ///
+    /// # Example
+    /// ```
+    /// let instancia = Instancia::desde_tsplib("inputs/berlin52.tsp")?;
+    /// ```
+    pub fn desde_tsplib(ruta: &str) -> std::io::Result<Self> {
+        let contenido = fs::read_to_string(ruta)?;
+        let invalido = |mensaje: String| Error::new(ErrorKind::InvalidData, format!("{}: {}", ruta, mensaje));
+        let mut nombre = ruta.to_string();
+        let mut metrica = Metrica::Euclidiana;
+        let mut lineas = contenido.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
+        for linea in lineas.by_ref() {
+            if linea.starts_with("NODE_COORD_SECTION") {
+                break;
+            }
+            let Some((clave, valor)) = linea.split_once(':') else {
+                continue;
+            };
+            match (clave.trim(), valor.trim()) {
+                ("NAME", valor) => nombre = valor.to_string(),
+                ("EDGE_WEIGHT_TYPE", "EUC_2D") => metrica = Metrica::Euclidiana,
+                ("EDGE_WEIGHT_TYPE", "GEO") => metrica = Metrica::Geografica,
+                ("EDGE_WEIGHT_TYPE", tipo) => return Err(invalido(format!("Tipo de peso no soportado {}", tipo))),
+                _ => {}
+            }
+        }
+        let mut ciudades = Vec::new();
+        let mut coordenadas = Vec::new();
+        for linea in lineas.take_while(|l| *l != "EOF") {
+            let campos: Vec<&str> = linea.split_whitespace().collect();
+            let [id, x, y] = campos[..] else {
+                return Err(invalido(format!("Renglon invalido {}", linea)));
+            };
+            let numero = |campo: &str| campo.parse::<f64>().map_err(|_| invalido(format!("Numero invalido {}", campo)));
+            let (x, y) = (numero(x)?, numero(y)?);
+            ciudades.push(id.parse::<i64>().map_err(|_| invalido(format!("Ciudad invalida {}", id)))?);
+            coordenadas.push(match metrica {
+                Metrica::Euclidiana => (x, y),
+                Metrica::Geografica => (grados_tsplib(x), grados_tsplib(y)),
+            });
+        }
+        validar_ciudades(&ciudades).map_err(invalido)?;
+        Ok(Self::desde_coordenadas(&nombre, ciudades, coordenadas, metrica))
+    }
+
+    /// Constructor de la instancia a partir de un csv de coordenadas.
+    ///
+    /// Cada renglon tiene la forma `id,x,y`, el primer renglon se omite si es un encabezado.
+    ///
+    /// # Example
+    /// ```
+    /// let instancia = Instancia::desde_csv("inputs/ciudades.csv", Metrica::Geografica)?;
+    /// ```
+    pub fn desde_csv(ruta: &str, metrica: Metrica) -> std::io::Result<Self> {
+        let contenido = fs::read_to_string(ruta)?;
+        let mut ciudades = Vec::new();
+        let mut coordenadas = Vec::new();
+        for (i, linea) in contenido.lines().map(|l| l.trim()).enumerate().filter(|(_, l)| !l.is_empty()) {
+            let campos: Vec<&str> = linea.split(',').map(|c| c.trim()).collect();
+            let renglon = match campos[..] {
+                [id, x, y] => id.parse::<i64>().ok().zip(x.parse::<f64>().ok()).zip(y.parse::<f64>().ok()),
+                _ => None,
+            };
+            match renglon {
+                Some(((id, x), y)) => {
+                    ciudades.push(id);
+                    coordenadas.push((x, y));
+                }
+                None if i == 0 => continue,
+                None => return Err(Error::new(ErrorKind::InvalidData, format!("{}: Renglon invalido {}", ruta, linea))),
+            }
+        }
+        validar_ciudades(&ciudades).map_err(|mensaje| Error::new(ErrorKind::InvalidData, format!("{}: {}", ruta, mensaje)))?;
+        Ok(Self::desde_coordenadas(ruta, ciudades, coordenadas, metrica))
+    }
+
+    /// Funcion encargada de construir la instancia una vez calculadas sus distancias.
+    ///
+    /// Recibe las distancias de las aristas existentes ordenadas de menor a mayor, a partir de las cuales
+    /// se calculan el normalizador y la distancia maxima.
+    ///
+    fn construir(nombre: &str, ciudades: Vec<i64>, coordenadas: Vec<(f64, f64)>, distancias: Vec<f64>, aristas: Vec<bool>, existentes: &[f64], hash: String) -> Self {
+        let maximo = ciudades.iter().copied().max().unwrap_or(0).max(0) as usize;
+        let mut posiciones = vec![usize::MAX; maximo + 1];
+        for (i, ciudad) in ciudades.iter().enumerate() {
+            posiciones[*ciudad as usize] = i;
+        }
+        Instancia {
+            nombre: nombre.to_string(),
+            normalizador: normalizador(existentes, ciudades.len()),
+            distancia_maxima: distancia_maxima(existentes),
+            ciudades,
+            coordenadas,
+            hash,
+            posiciones,
+            distancias,
+            aristas,
+        }
+    }
+
+    /// Funcion encargada de obtener la cantidad de ciudades de la instancia.
+    ///
+    pub fn len(&self) -> usize {
+        self.ciudades.len()
+    }
+
+    /// Funcion encargada de indicar si la instancia no tiene ciudades.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.ciudades.is_empty()
+    }
+
+    /// Funcion encargada de obtener el peso entre un par de ciudades a partir de sus identificadores.
+    ///
+    /// # Example
+    /// ```
+    /// let p:f64 = instancia.peso(1, 7);
+    /// ```
+    pub fn peso(&self, u: i64, v: i64) -> f64 {
+        let n = self.ciudades.len();
+        self.distancias[self.posiciones[u as usize] * n + self.posiciones[v as usize]]
+    }
+
+    /// Funcion encargada de indicar si la arista entre dos ciudades existe, es decir, si su peso no esta penalizado.
+    ///
+    pub fn existe_arista(&self, u: i64, v: i64) -> bool {
+        let n = self.ciudades.len();
+        self.aristas[self.posiciones[u as usize] * n + self.posiciones[v as usize]]
+    }
+
+    /// Funcion encargada de obtener las coordenadas de una ciudad a partir de su identificador.
+    ///
+    pub fn coordenada(&self, u: i64) -> (f64, f64) {
+        self.coordenadas[self.posiciones[u as usize]]
+    }
+
+    /// Funcion encargada de indicar si un recorrido es una permutacion de las ciudades de la instancia.
+    ///
+    /// Los recorridos que se leen de archivos deben verificarse antes de evaluarse, pues `peso` supone que las ciudades pertenecen a la instancia.
+    ///
+    pub fn es_permutacion(&self, recorrido: &[i64]) -> bool {
+        let mut ordenado = recorrido.to_vec();
+        ordenado.sort();
+        let mut ciudades = self.ciudades.clone();
+        ciudades.sort();
+        ordenado == ciudades
+    }
+
+    /// Constructor de la instancia a partir de la base de datos `tsp.db`, usando un cache en disco.
+    ///
+    /// Los parametros estan dados por:
+    /// - nombre:&str : Nombre de la instancia.
+    /// - ciudades:`&[i64]` : Identificadores de las ciudades.
+    /// - directorio:&str : Directorio del cache.
+    ///
+    /// El archivo del cache se identifica con el hash de la lista de ciudades y el checksum de `tsp.db`, por lo que al
+    /// modificar la base de datos se vuelve a calcular. El checksum se guarda en el directorio del cache junto con el tamaño y la fecha
+    /// de modificacion de `tsp.db`, y unicamente se vuelve a leer la base de datos completa cuando alguno de ellos cambia. Si el archivo no existe o no corresponde a las ciudades, la instancia
+    /// se carga de la base de datos y se escribe en el cache; un error al escribirlo no impide regresar la instancia.
+    ///
+    /// # Example
+    /// ```
+    /// let instancia = Instancia::cargar_db_cache("inputs/input-40.tsp", &ciudades, "cache")?;
+    /// ```
+    pub fn cargar_db_cache(nombre: &str, ciudades: &[i64], directorio: &str) -> rusqlite::Result<Self> {
+        let Ok(checksum) = checksum_guardado("tsp.db", directorio) else {
+            return Self::cargar_db(nombre, ciudades);
+        };
+        let ruta = Path::new(directorio).join(format!("{}_{}.bin", hash_instancia(ciudades), checksum));
+        if let Ok(instancia) = Self::leer_binario(&ruta, nombre) && instancia.ciudades == ciudades {
+            return Ok(instancia);
+        }
+        let instancia = Self::cargar_db(nombre, ciudades)?;
+        let _ = fs::create_dir_all(directorio).and_then(|_| instancia.escribir_binario(&ruta));
+        Ok(instancia)
+    }
+
+    /// Funcion encargada de escribir la instancia completa en un archivo binario.
+    ///
+    /// Se escriben las ciudades, coordenadas, el hash, el normalizador, la distancia maxima y las matrices de distancias y aristas,
+    /// de manera que al leerla no es necesario volver a calcular nada. El nombre no se escribe.
+    ///
+    /// # Example
+    /// ```
+    /// instancia.escribir_binario(Path::new("cache/instancia.bin"))?;
+    /// ```
+    pub fn escribir_binario(&self, ruta: &Path) -> std::io::Result<()> {
+        let temporal = ruta.with_extension("tmp");
+        let mut file = BufWriter::new(File::create(&temporal)?);
+        file.write_all(MAGICO)?;
+        file.write_all(&(self.ciudades.len() as u64).to_le_bytes())?;
+        file.write_all(&(self.hash.len() as u64).to_le_bytes())?;
+        file.write_all(self.hash.as_bytes())?;
+        file.write_all(&self.normalizador.to_le_bytes())?;
+        file.write_all(&self.distancia_maxima.to_le_bytes())?;
+        for ciudad in &self.ciudades {
+            file.write_all(&ciudad.to_le_bytes())?;
+        }
+        for (x, y) in &self.coordenadas {
+            file.write_all(&x.to_le_bytes())?;
+            file.write_all(&y.to_le_bytes())?;
+        }
+        for distancia in &self.distancias {
+            file.write_all(&distancia.to_le_bytes())?;
+        }
+        file.write_all(&self.aristas.iter().map(|a| *a as u8).collect::<Vec<u8>>())?;
+        file.flush()?;
+        drop(file);
+        fs::rename(&temporal, ruta)
+    }
+
+    /// Funcion encargada de leer una instancia escrita con `escribir_binario`.
+    ///
+    /// Antes de reservar memoria se verifica que la cantidad de ciudades y la longitud del hash correspondan al tamaño del archivo,
+    /// por lo que un archivo truncado o corrupto regresa un error de tipo `InvalidData` en lugar de agotar la memoria.
+    ///
+    /// # Example
+    /// ```
+    /// let instancia = Instancia::leer_binario(Path::new("cache/instancia.bin"), "inputs/input-40.tsp")?;
+    /// ```
+    pub fn leer_binario(ruta: &Path, nombre: &str) -> std::io::Result<Self> {
+        let archivo = File::open(ruta)?;
+        let tamano = archivo.metadata()?.len();
+        let mut lector = BufReader::new(archivo);
+        let mut magico = [0u8; 4];
+        lector.read_exact(&mut magico)?;
+        if &magico != MAGICO {
+            return Err(Error::new(ErrorKind::InvalidData, format!("{} no es una instancia binaria", ruta.display())));
+        }
+        let n = u64::from_le_bytes(leer_u64(&mut lector)?);
+        let longitud_hash = u64::from_le_bytes(leer_u64(&mut lector)?);
+        let esperado = n
+            .checked_mul(n)
+            .and_then(|nn| nn.checked_mul(9))
+            .and_then(|matrices| n.checked_mul(24).and_then(|vectores| vectores.checked_add(matrices)))
+            .and_then(|datos| datos.checked_add(longitud_hash))
+            .and_then(|datos| datos.checked_add(36));
+        if esperado != Some(tamano) {
+            return Err(Error::new(ErrorKind::InvalidData, format!("{} no corresponde al tamaño de una instancia binaria", ruta.display())));
+        }
+        let n = n as usize;
+        let mut hash = vec![0u8; longitud_hash as usize];
+        lector.read_exact(&mut hash)?;
+        let hash = String::from_utf8(hash).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let normalizador = f64::from_le_bytes(leer_u64(&mut lector)?);
+        let distancia_maxima = f64::from_le_bytes(leer_u64(&mut lector)?);
+        let mut ciudades = Vec::with_capacity(n);
+        for _ in 0..n {
+            ciudades.push(i64::from_le_bytes(leer_u64(&mut lector)?));
+        }
+        let mut coordenadas = Vec::with_capacity(n);
+        for _ in 0..n {
+            coordenadas.push((f64::from_le_bytes(leer_u64(&mut lector)?), f64::from_le_bytes(leer_u64(&mut lector)?)));
+        }
+        let mut distancias = Vec::with_capacity(n * n);
+        for _ in 0..n * n {
+            distancias.push(f64::from_le_bytes(leer_u64(&mut lector)?));
+        }
+        let mut aristas = vec![0u8; n * n];
+        lector.read_exact(&mut aristas)?;
+        validar_ciudades(&ciudades).map_err(|mensaje| Error::new(ErrorKind::InvalidData, format!("{}: {}", ruta.display(), mensaje)))?;
+        let mut instancia = Self::construir(nombre, ciudades, coordenadas, distancias, aristas.iter().map(|a| *a != 0).collect(), &[], hash);
+        instancia.normalizador = normalizador;
+        instancia.distancia_maxima = distancia_maxima;
+        Ok(instancia)
+    }
+}
+
+/// Funcion encargada de leer 8 bytes de un archivo binario.
+///
+fn leer_u64(lector: &mut BufReader<File>) -> std::io::Result<[u8; 8]> {
+    let mut bytes = [0u8; 8];
+    lector.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Funcion encargada de verificar los identificadores de las ciudades leidas de un archivo.
+///
+/// Los identificadores deben ser distintos y estar entre 0 y `MAXIMO_ID`, en otro caso regresa el motivo del error.
+///
+fn validar_ciudades(ciudades: &[i64]) -> Result<(), String> {
+    if let Some(ciudad) = ciudades.iter().find(|c| !(0..=MAXIMO_ID).contains(*c)) {
+        return Err(format!("Ciudad {} fuera del rango 0..={}", ciudad, MAXIMO_ID));
+    }
+    let mut vista = vec![false; ciudades.iter().copied().max().unwrap_or(0) as usize + 1];
+    for &ciudad in ciudades {
+        if vista[ciudad as usize] {
+            return Err(format!("Ciudad {} repetida", ciudad));
+        }
+        vista[ciudad as usize] = true;
+    }
+    Ok(())
+}
+
+/// Funcion encargada de calcular el checksum del contenido de un archivo.
+///
+/// # Example
+/// ```
+/// let checksum = checksum_archivo("tsp.db")?;
+/// ```
+pub fn checksum_archivo(ruta: &str) -> std::io::Result<String> {
+    Ok(fnv(fs::read(ruta)?.into_iter()))
+}
+
+/// Funcion encargada de obtener el checksum de un archivo reutilizando el que se guardo en el directorio dado.
+///
+/// El checksum se guarda en `<directorio>/<archivo>.checksum` junto con el tamaño y la fecha de modificacion del archivo.
+/// Si alguno de ellos cambio, o no se ha guardado, se calcula de nuevo con `checksum_archivo` y se guarda;
+/// un error al guardarlo no impide regresar el checksum.
+///
+/// # Example
+/// ```
+/// let checksum = checksum_guardado("tsp.db", "cache")?;
+/// ```
+pub fn checksum_guardado(ruta: &str, directorio: &str) -> std::io::Result<String> {
+    let metadatos = fs::metadata(ruta)?;
+    let modificacion = metadatos.modified()?.duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let firma = format!("{} {}", metadatos.len(), modificacion);
+    let nombre = Path::new(ruta).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let guardado = Path::new(directorio).join(format!("{}.checksum", nombre));
+    if let Ok(contenido) = fs::read_to_string(&guardado)
+        && let Some((firma_guardada, checksum)) = contenido.trim().rsplit_once(' ')
+        && firma_guardada == firma
+    {
+        return Ok(checksum.to_string());
+    }
+    let checksum = checksum_archivo(ruta)?;
+    let _ = fs::create_dir_all(directorio).and_then(|_| fs::write(&guardado, format!("{} {}", firma, checksum)));
+    Ok(checksum)
+}
+
+/// Funcion encargada de obtener la distancia maxima con la que se penalizan las aristas que no existen.
+///
+/// Recibe la lista ordenada de distancias de las aristas existentes. Si la lista es vacia la distancia maxima es 1,
+/// el mismo valor que toma el normalizador en ese caso.
+///
+fn distancia_maxima(lista_ordenada: &[f64]) -> f64 {
+    lista_ordenada.last().copied().unwrap_or(1.0)
+}
+
+/// Funcion encargada de obtener el normalizador.
+///
+/// La funcion recibe la lista ordenada de distancias de las aristas existentes entre las ciudades de la instancia y la cantidad de ciudades.
+/// El normalizador es la suma de las n-1 mayores distancias de la lista, es decir, una cota de la longitud de un recorrido que solo usa aristas existentes.
+///
+/// Si la lista tiene menos de n-1 distancias, se suman todas y cada distancia faltante se cuenta como la distancia maxima.
+/// Si no hay distancias o la suma es cero (por ejemplo con una sola ciudad), el normalizador es 1 y el costo es la longitud del recorrido.
+///
+/// Regresa el normalizador en forma f64.
+///
+fn normalizador(lista_ordenada: &[f64], n: usize) -> f64 {
+    let requeridas = n.saturating_sub(1);
+    let Some(maxima) = lista_ordenada.last() else {
+        return 1.0;
+    };
+    let tomadas = requeridas.min(lista_ordenada.len());
+    let suma: f64 = lista_ordenada[lista_ordenada.len() - tomadas..].iter().sum::<f64>() + (requeridas - tomadas) as f64 * maxima;
+    if suma > 0.0 { suma } else { 1.0 }
+}
+
+/// Funcion encargada de escribir una instancia en formato TSPLIB.
+///
+/// Los parametros estan dados por:
+/// - ruta:&str : Ruta del archivo.
+/// - nombre:&str : Nombre de la instancia.
+/// - ciudades:`&[i64]` : Identificadores de las ciudades.
+/// - coordenadas:`&[(f64, f64)]` : Coordenadas de cada ciudad.
+/// - metrica:Metrica : Con la euclidiana se escribe `EUC_2D`, y con la geografica `GEO` con las coordenadas en grados y minutos.
+///
+/// # Example
+/// ```
+/// escribir_tsplib("inputs/uniforme-100.tsp", "uniforme-100", &ciudades, &coordenadas, Metrica::Euclidiana)?;
+/// ```
+pub fn escribir_tsplib(ruta: &str, nombre: &str, ciudades: &[i64], coordenadas: &[(f64, f64)], metrica: Metrica) -> std::io::Result<()> {
+    let mut file = BufWriter::new(File::create(ruta)?);
+    writeln!(file, "NAME : {}", nombre)?;
+    writeln!(file, "TYPE : TSP")?;
+    writeln!(file, "DIMENSION : {}", ciudades.len())?;
+    match metrica {
+        Metrica::Euclidiana => writeln!(file, "EDGE_WEIGHT_TYPE : EUC_2D")?,
+        Metrica::Geografica => writeln!(file, "EDGE_WEIGHT_TYPE : GEO")?,
+    }
+    writeln!(file, "NODE_COORD_SECTION")?;
+    for (ciudad, (x, y)) in ciudades.iter().zip(coordenadas) {
+        match metrica {
+            Metrica::Euclidiana => writeln!(file, "{} {} {}", ciudad, x, y)?,
+            Metrica::Geografica => writeln!(file, "{} {:.4} {:.4}", ciudad, minutos_tsplib(*x), minutos_tsplib(*y))?,
+        }
+    }
+    writeln!(file, "EOF")?;
+    file.flush()
+}
+
+/// Funcion encargada de pasar una coordenada de TSPLIB en grados y minutos a grados.
+///
+fn grados_tsplib(valor: f64) -> f64 {
+    let grados = valor.trunc();
+    grados + 5.0 * (valor - grados) / 3.0
+}
+
+/// Funcion encargada de pasar una coordenada en grados a grados y minutos de TSPLIB.
+///
+fn minutos_tsplib(valor: f64) -> f64 {
+    let grados = valor.trunc();
+    grados + 3.0 * (valor - grados) / 5.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_desde_coordenadas() {
+        let instancia = Instancia::desde_coordenadas("prueba", vec![7, 3, 5], vec![(0.0, 0.0), (3.0, 4.0), (6.0, 8.0)], Metrica::Euclidiana);
+        assert_eq!(instancia.len(), 3);
+        assert_eq!(instancia.peso(7, 3), 5.0);
+        assert_eq!(instancia.peso(5, 7), 10.0);
+        assert!(instancia.existe_arista(3, 5));
+        assert!(!instancia.existe_arista(3, 3));
+        assert_eq!(instancia.coordenada(5), (6.0, 8.0));
+        assert_eq!(instancia.distancia_maxima, 10.0);
+        assert_eq!(instancia.normalizador, 15.0);
+    }
+
+    #[test]
+    fn ok_aristas_penalizadas() {
+        let distancias = vec![0.0, 1.0, 9.0, 1.0, 0.0, 2.0, 9.0, 2.0, 0.0];
+        let aristas = vec![false, true, false, true, false, true, false, true, false];
+        let instancia = Instancia::new("prueba", vec![1, 2, 3], vec![(0.0, 0.0); 3], distancias, aristas);
+        assert!(!instancia.existe_arista(1, 3));
+        assert_eq!(instancia.peso(1, 3), 9.0);
+        assert_eq!(instancia.distancia_maxima, 2.0);
+        assert_ne!(instancia.hash, hash_instancia(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn ok_desde_db_sin_aristas() {
+        let mut db = CityDB::new(&vec![1, 2, 3]);
+        for c in 1..=3 {
+            db.coordenadas[c as usize] = (c as f64, 0.0);
+        }
+        let instancia = Instancia::desde_db(db.clone(), "prueba", &[1, 2, 3]);
+        assert_eq!(instancia.normalizador, 1.0);
+        assert_eq!(instancia.distancia_maxima, 1.0);
+        assert_eq!(instancia.peso(1, 3), distancia_natural((1.0, 0.0), (3.0, 0.0)));
+
+        db.aristas[1093 + 2] = true;
+        db.aristas[2 * 1093 + 1] = true;
+        db.data[1093 + 2] = 5.0;
+        db.data[2 * 1093 + 1] = 5.0;
+        db.distancias_tsp = vec![5.0];
+        let instancia = Instancia::desde_db(db, "prueba", &[1, 2, 3]);
+        assert_eq!(instancia.normalizador, 10.0);
+        assert_eq!(instancia.distancia_maxima, 5.0);
+        assert_eq!(instancia.peso(2, 1), 5.0);
+        assert_eq!(instancia.peso(2, 3), distancia_natural((2.0, 0.0), (3.0, 0.0)) * 5.0);
+    }
+
+    #[test]
+    fn ok_desde_tsplib() {
+        let ruta = std::env::temp_dir().join("proyecto1_ok_desde_tsplib.tsp");
+        fs::write(&ruta, "NAME : prueba\nTYPE : TSP\nDIMENSION : 3\nEDGE_WEIGHT_TYPE : EUC_2D\nNODE_COORD_SECTION\n1 0 0\n2 3 4\n3 6 8\nEOF\n").unwrap();
+        let instancia = Instancia::desde_tsplib(ruta.to_str().unwrap()).unwrap();
+        fs::remove_file(&ruta).unwrap();
+        assert_eq!(instancia.nombre, "prueba");
+        assert_eq!(instancia.ciudades, vec![1, 2, 3]);
+        assert_eq!(instancia.peso(1, 3), 10.0);
+        assert!((grados_tsplib(19.30) - 19.5).abs() < 1e-9);
+        assert!((grados_tsplib(minutos_tsplib(-99.13)) + 99.13).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ok_desde_csv() {
+        let ruta = std::env::temp_dir().join("proyecto1_ok_desde_csv.csv");
+        fs::write(&ruta, "id,x,y\n4,0,0\n9,3,4\n").unwrap();
+        let instancia = Instancia::desde_csv(ruta.to_str().unwrap(), Metrica::Euclidiana).unwrap();
+        fs::remove_file(&ruta).unwrap();
+        assert_eq!(instancia.ciudades, vec![4, 9]);
+        assert_eq!(instancia.peso(9, 4), 5.0);
+        for contenido in ["-1,0,0\n2,1,1\n", "4,0,0\n4,1,1\n", "4,0,0\n99999999999,1,1\n"] {
+            fs::write(&ruta, contenido).unwrap();
+            assert_eq!(Instancia::desde_csv(ruta.to_str().unwrap(), Metrica::Euclidiana).unwrap_err().kind(), ErrorKind::InvalidData);
+        }
+        fs::remove_file(&ruta).unwrap();
+    }
+
+    #[test]
+    fn ok_binario() {
+        let distancias = vec![0.0, 1.0, 9.0, 1.0, 0.0, 2.0, 9.0, 2.0, 0.0];
+        let aristas = vec![false, true, false, true, false, true, false, true, false];
+        let instancia = Instancia::new("prueba", vec![4, 2, 7], vec![(0.5, 1.0 / 3.0), (2.0, 3.0), (4.0, 5.0)], distancias, aristas);
+        let ruta = std::env::temp_dir().join("proyecto1_ok_binario.bin");
+        instancia.escribir_binario(&ruta).unwrap();
+        let leida = Instancia::leer_binario(&ruta, "prueba").unwrap();
+        let mut corrupto = fs::read(&ruta).unwrap();
+        corrupto[4..12].copy_from_slice(&u64::MAX.to_le_bytes());
+        fs::write(&ruta, &corrupto).unwrap();
+        assert_eq!(Instancia::leer_binario(&ruta, "prueba").unwrap_err().kind(), ErrorKind::InvalidData);
+        fs::remove_file(&ruta).unwrap();
+        assert_eq!(leida, instancia);
+        assert!(!leida.existe_arista(4, 7));
+        assert!(leida.es_permutacion(&[7, 4, 2]));
+        assert!(!leida.es_permutacion(&[7, 4, 4]));
+        assert!(!leida.es_permutacion(&[7, 4, 2, 5]));
+        assert_eq!(leida.peso(7, 2), 2.0);
+    }
+
+    #[test]
+    fn ok_checksum_guardado() {
+        let directorio = std::env::temp_dir().join("proyecto1_ok_checksum_guardado");
+        let _ = fs::remove_dir_all(&directorio);
+        fs::create_dir_all(&directorio).unwrap();
+        let archivo = directorio.join("datos.db");
+        let (ruta, cache) = (archivo.to_str().unwrap(), directorio.to_str().unwrap());
+        fs::write(&archivo, "abc").unwrap();
+        let checksum = checksum_guardado(ruta, cache).unwrap();
+        assert_eq!(checksum, checksum_archivo(ruta).unwrap());
+        let guardado = directorio.join("datos.db.checksum");
+        let contenido = fs::read_to_string(&guardado).unwrap().replace(&checksum, "reutilizado");
+        fs::write(&guardado, contenido).unwrap();
+        assert_eq!(checksum_guardado(ruta, cache).unwrap(), "reutilizado");
+        fs::write(&archivo, "abcd").unwrap();
+        assert_eq!(checksum_guardado(ruta, cache).unwrap(), checksum_archivo(ruta).unwrap());
+        fs::remove_dir_all(&directorio).unwrap();
+    }
+
+    #[test]
+    fn ok_normalizador() {
+        assert_eq!(normalizador(&[1.0, 2.0, 3.0, 4.0], 3), 7.0);
+        assert_eq!(normalizador(&[1.0, 2.0], 3), 3.0);
+        assert_eq!(normalizador(&[2.0], 4), 6.0);
+        assert_eq!(normalizador(&[], 4), 1.0);
+        assert_eq!(normalizador(&[5.0], 1), 1.0);
+    }
+}
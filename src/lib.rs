@@ -0,0 +1,162 @@
+//! Recocido simulado por aceptacion por umbrales para aproximar soluciones del TSP.
+//!
+//! La biblioteca expone la instancia (`Instancia`, construida a partir de `tsp.db`, de un archivo TSPLIB,
+//! de un csv de coordenadas o directamente), el recocido (`Tsp` con sus `Parametros`,
+//! observadores, cancelacion y puntos de control), el resultado de una ejecucion (`Resultado`) y la busqueda local,
+//! ademas de la generacion de graficas y el registro de resultados que usa el binario.
+//!
+//! # Example
+//! ```
+//! let instancia = Instancia::cargar_db("inputs/input-40.tsp", &ciudades)?;
+//! let resolucion = resolver(&instancia, &Configuracion::default(), 7);
+//! println!("Costo {} Recorrido {:?}", resolucion.resultado.costo, resolucion.resultado.solucion);
+//! let mejorado = busqueda_local(&instancia, &recorrido);
+//! ```
+
+pub mod db;
+pub mod grafica;
+pub mod instancia;
+pub mod generador;
+pub mod analisis;
+pub mod tsp;
+pub mod generador_svg;
+pub mod estadisticas;
+pub mod resultado;
+pub mod indice;
+pub mod base_resultados;
+pub mod mapa_svg;
+pub mod convergencia;
+pub mod comparacion;
+pub mod traza;
+pub mod observador;
+pub mod cancelacion;
+pub mod punto_control;
+
+pub use cancelacion::Cancelacion;
+pub use db::CityDB;
+pub use grafica::Grafica;
+pub use instancia::{Instancia, Metrica};
+pub use observador::{Control, Observador, Progreso};
+pub use punto_control::PuntoControl;
+pub use resultado::{Estado, Resultado};
+pub use tsp::{Parametros, Tsp};
+
+use comparacion::{curva_mejor, PUNTOS_CURVA};
+use observador::DetencionTemprana;
+use std::time::Instant;
+use traza::{Decimador, Traza};
+
+/// Estructura correspondiente a la configuracion de una ejecucion de `resolver`.
+///
+/// - parametros:Parametros : Parametros del recocido.
+/// - detener:`Option<usize>` : Cantidad de lotes consecutivos sin mejorar la mejor solucion tras los cuales se detiene el recocido, en caso de darse.
+/// - limite_traza:usize : Cantidad maxima de puntos de la traza de soluciones aceptadas que se conservan, con 0 no se registra la traza.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Configuracion {
+    pub parametros: Parametros,
+    pub detener: Option<usize>,
+    pub limite_traza: usize,
+}
+
+/// Estructura correspondiente a lo que regresa `resolver`.
+///
+/// - resultado:Resultado : Mejor recorrido despues del barrido, costos, longitud y estadisticas del recocido.
+/// - traza:`Vec<(f64, bool)>` : Muestra de los costos de las soluciones aceptadas y si cada una mejoro la mejor solucion, vacia si no se solicito.
+/// - paso:usize : Cantidad de soluciones aceptadas que resume cada punto de la traza.
+///
+#[derive(Clone, Debug, Default)]
+pub struct Resolucion {
+    pub resultado: Resultado,
+    pub traza: Vec<(f64, bool)>,
+    pub paso: usize,
+}
+
+/// Funcion encargada de resolver una instancia con una semilla.
+///
+/// Los parametros estan dados por:
+/// - instancia:&Instancia : Instancia a resolver.
+/// - configuracion:&Configuracion : Parametros del recocido, detencion temprana y traza.
+/// - semilla:i64 : Semilla del recocido.
+///
+/// Ejecuta el recocido y el barrido de la misma forma que el binario, por lo que con la misma semilla se obtiene el mismo recorrido.
+/// La instancia unicamente se toma prestada, por lo que resolver varias semillas no copia sus matrices.
+/// Si se solicita la traza, la curva de la mejor solucion del resultado se calcula a partir de ella.
+/// Para registrar otros observadores, cancelar o guardar puntos de control se configura el `Tsp` directamente y
+/// el resultado se obtiene con `Resultado::desde_tsp`.
+///
+/// # Example
+/// ```
+/// let resolucion = resolver(&instancia, &Configuracion { detener: Some(200), ..Configuracion::default() }, 7);
+/// println!("Costo {}", resolucion.resultado.costo);
+/// ```
+pub fn resolver(instancia: &Instancia, configuracion: &Configuracion, semilla: i64) -> Resolucion {
+    let inicio = Instant::now();
+    let mut tsp = Tsp::con_parametros(configuracion.parametros.clone(), instancia, semilla);
+    if let Some(paciencia) = configuracion.detener {
+        tsp.agregar_observador(DetencionTemprana::new(paciencia));
+    }
+    tsp.establecer_traza(if configuracion.limite_traza == 0 {
+        Traza::Apagada
+    } else {
+        Traza::Memoria(Decimador::new(configuracion.limite_traza))
+    });
+    tsp.generar_primer_solucion();
+    tsp.aceptacion_por_umbrales();
+    let mut resultado = Resultado::desde_tsp(&tsp, inicio.elapsed().as_secs_f64());
+    // La traza de resolver es apagada o en memoria, cuya muestra no lee ni escribe archivos, por lo que no puede fallar.
+    let (traza, paso) = tsp.tomar_traza().muestra(configuracion.limite_traza).expect("La traza en memoria no realiza operaciones de entrada y salida");
+    resultado.curva_mejor = curva_mejor(&traza, PUNTOS_CURVA);
+    Resolucion { resultado, traza, paso }
+}
+
+/// Funcion encargada de mejorar un recorrido con el barrido.
+///
+/// Recibe la instancia y el recorrido, el cual debe ser una permutacion de las ciudades de la instancia, y regresa el recorrido
+/// obtenido al intercambiar pares de ciudades mientras mejore el costo. Es la busqueda local que se aplica despues del recocido.
+///
+/// # Example
+/// ```
+/// let recorrido = busqueda_local(&instancia, &instancia.ciudades);
+/// ```
+pub fn busqueda_local(instancia: &Instancia, recorrido: &[i64]) -> Vec<i64> {
+    let mut recorrido = recorrido.to_vec();
+    tsp::barrido_recorrido(instancia, &mut recorrido);
+    recorrido
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generar_instancia(n: i64) -> Instancia {
+        let ciudades: Vec<i64> = (1..=n).collect();
+        let coordenadas = ciudades.iter().map(|c| ((c * 7 % 11) as f64, (c * c % 13) as f64)).collect();
+        Instancia::desde_coordenadas("prueba", ciudades, coordenadas, Metrica::Euclidiana)
+    }
+
+    #[test]
+    fn ok_resolver() {
+        let instancia = generar_instancia(8);
+        let parametros = Parametros { tamano_lote: 20, ..Parametros::default() };
+        let configuracion = Configuracion { parametros, detener: Some(5), limite_traza: 1000 };
+        let resolucion = resolver(&instancia, &configuracion, 3);
+        let resultado = &resolucion.resultado;
+        assert!(!resolucion.traza.is_empty());
+        assert_eq!(resolucion.paso, 1);
+        assert!(!resultado.curva_mejor.is_empty());
+        assert_eq!(resultado.costo, tsp::costo_recorrido(&instancia, &resultado.solucion));
+        assert_eq!(busqueda_local(&instancia, &resultado.solucion), resultado.solucion);
+        assert_eq!(resolver(&instancia, &configuracion, 3).resultado.solucion, resultado.solucion);
+    }
+
+    #[test]
+    fn ok_busqueda_local() {
+        let instancia = generar_instancia(7);
+        let recorrido = busqueda_local(&instancia, &instancia.ciudades);
+        let mut ordenado = recorrido.clone();
+        ordenado.sort();
+        assert_eq!(ordenado, instancia.ciudades);
+        assert!(tsp::costo_recorrido(&instancia, &recorrido) <= tsp::costo_recorrido(&instancia, &instancia.ciudades));
+    }
+}
@@ -1,149 +1,729 @@
-mod db;
-mod grafica;
-mod tsp;
-mod generador_svg;
-
-use db::CityDB;
-use grafica::Grafica;
-use std::fs::File;
+use proyecto1::instancia::{escribir_tsplib, Instancia, Metrica};
+use proyecto1::db::CityDB;
+use proyecto1::analisis::{aristas_penalizadas, Analisis, Evaluacion};
+use proyecto1::generador::{ciudades_aleatorias, coordenadas_sinteticas, escribir_lista, Caja, Distribucion};
 use std::fs;
 use std::env;
-use tsp::Tsp;
+use proyecto1::tsp::{Parametros, Tsp};
 use std::thread;
 use std::sync::{Arc, Mutex};
-use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use std::io::Write;
 use chrono::Local;
-use crate::generador_svg::generar;
+use proyecto1::generador_svg::{generar, OpcionesSvg};
+use proyecto1::estadisticas::{escribir_csv, Resumen};
+use proyecto1::resultado::{hash_parametros, Estado, Resultado};
+use proyecto1::traza::{leer_traza, Decimador, Traza, LIMITE_TRAZA};
+use proyecto1::observador::{DetencionTemprana, Instantaneas};
+use proyecto1::cancelacion::Cancelacion;
+use proyecto1::punto_control::PuntoControl;
+use proyecto1::indice::Indice;
+use proyecto1::base_resultados::BaseResultados;
+use proyecto1::convergencia::generar_convergencia;
+use proyecto1::comparacion::{curva_mejor, escribir_curvas, generar_comparacion, leer_curvas, PUNTOS_CURVA};
+use proyecto1::mapa_svg::{generar_animacion, generar_cuadros, generar_mapa, Proyeccion};
+use std::time::Instant;
+
+/// Estructura correspondiente a las opciones de ejecucion dadas en la linea de comandos.
+///
+/// - instancia:String : Nombre del archivo de ciudades.
+/// - cadena:String : Cadena para formatear el nombre de los archivos de salida.
+/// - svg:bool : Indica si se genera el svg de las soluciones aceptadas.
+/// - opciones_svg:OpcionesSvg : Dimensiones, colores y simplificacion del svg de las soluciones aceptadas.
+/// - traza:`Option<&str>` : Extension del archivo en el que se escribe la traza completa de soluciones aceptadas, csv o bin.
+/// - limite_traza:usize : Cantidad maxima de puntos de la traza en memoria, con 0 no se registra la traza.
+/// - convergencia:bool : Indica si se genera la grafica de convergencia con plotters en svg y png.
+/// - mapa:bool : Indica si se genera el mapa de la mejor solucion.
+/// - animacion:usize : Cantidad maxima de instantaneas de la mejor solucion para la animacion, con 0 no se genera.
+/// - cuadros:bool : Indica si la animacion se genera como una secuencia de svgs numerados en lugar de un svg animado.
+/// - proyeccion:Proyeccion : Proyeccion con la que se generan el mapa y la animacion.
+/// - progreso:`Option<usize>` : Cantidad de lotes entre cada reporte del progreso, en caso de solicitarse.
+/// - detener:`Option<usize>` : Cantidad de lotes consecutivos sin mejora tras los cuales se detiene el recocido, en caso de solicitarse.
+/// - reanudar:bool : Indica si se omiten las semillas que ya se encuentran en el indice de resultados.
+/// - punto_control:`Option<usize>` : Cantidad de lotes entre cada punto de control, en caso de solicitarse.
+/// - continuar:bool : Indica si el recocido continua desde el punto de control de la semilla, en caso de existir.
+/// - cancelacion:Cancelacion : Token de cancelacion compartido por todas las semillas, se cancela con Ctrl-C.
+/// - parametros:Parametros : Parametros del recocido.
+///
+#[derive(Clone)]
+struct Opciones {
+    instancia: String,
+    cadena: String,
+    svg: bool,
+    opciones_svg: OpcionesSvg,
+    traza: Option<&'static str>,
+    limite_traza: usize,
+    convergencia: bool,
+    mapa: bool,
+    animacion: usize,
+    cuadros: bool,
+    proyeccion: Proyeccion,
+    progreso: Option<usize>,
+    detener: Option<usize>,
+    reanudar: bool,
+    punto_control: Option<usize>,
+    continuar: bool,
+    cancelacion: Cancelacion,
+    parametros: Parametros,
+}
+
+/// Cantidad maxima de puntos de la traza que se leen para generar las graficas.
+const PUNTOS_MUESTRA: usize = 100_000;
+
+/// Ruta del indice de resultados, en el que se registran las semillas completadas.
+const RUTA_INDICE: &str = "resultados/indice.csv";
+
+/// Ruta por omision de la base de datos de resultados.
+const RUTA_BASE_RESULTADOS: &str = "resultados/resultados.db";
+
+/// Directorio del cache de las instancias cargadas de la base de datos.
+const DIRECTORIO_CACHE: &str = "cache";
+
+/// Estructura correspondiente a los registros compartidos entre los hilos.
+///
+/// - indice:`Mutex<Indice>` : Indice de resultados en el que se registran las semillas completadas.
+/// - base:`Option<Mutex<BaseResultados>>` : Base de datos de resultados, en caso de que se haya solicitado.
+/// - base_traza:bool : Indica si tambien se guarda la traza en la base de datos.
+///
+struct Registros {
+    indice: Mutex<Indice>,
+    base: Option<Mutex<BaseResultados>>,
+    base_traza: bool,
+}
+
+impl Registros {
+
+    /// Constructor de los registros a partir de los argumentos.
+    ///
+    /// La base de resultados se abre unicamente si se dio la opcion `--db`, con la ruta indicada
+    /// o con la ruta por omision si la siguiente opcion no es una ruta.
+    ///
+    fn new(args: &[String]) -> Self {
+        let indice = Indice::cargar(RUTA_INDICE).expect("No se pudo leer el indice de resultados");
+        let base = args.iter().position(|a| a == "--db").map(|i| {
+            let ruta = args.get(i + 1).filter(|r| !r.starts_with('-')).map(|r| r.as_str()).unwrap_or(RUTA_BASE_RESULTADOS);
+            Mutex::new(BaseResultados::abrir(ruta).expect("No se pudo abrir la base de resultados"))
+        });
+        Registros {
+            indice: Mutex::new(indice),
+            base,
+            base_traza: args.iter().any(|a| a == "--db-traza"),
+        }
+    }
+}
 
 /// Funcion encargada de correr el algoritmo de tsp.
 ///
 /// Los parametros estan dados por:
 /// - semilla:i64 : Semilla la cual será usada en el tsp
-/// - numeros:`Vec<i64>` : Lista de ciudades del TSP.
-/// - cadena:String : Cadena para formatear el nombre del archivo.
+/// - instancia:&Instancia : Instancia del TSP, compartida por todas las semillas.
+/// - opciones:&Opciones : Opciones de ejecucion, entre ellas la cadena para formatear el nombre del archivo.
+/// - registros:&Registros : Indice y base de resultados en los que se registra la semilla al terminar.
 ///
-/// La funcion se encarga de lanzar el tsp con la semilla dada, para posteriormente escribir un archivo con los resultados obtenidos.
+/// La funcion se encarga de lanzar el tsp con la semilla dada, para posteriormente escribir un archivo json con los resultados obtenidos.
+/// Si se solicita, la traza de soluciones aceptadas se escribe conforme se acepta cada solucion en un archivo csv o binario,
+/// en otro caso se conserva en memoria una traza decimada. Las graficas se generan a partir de una muestra de la traza.
+/// La traza pertenece al tsp durante el recocido y se recupera al terminar; las instantaneas, el progreso y la detencion temprana se registran como observadores del recocido.
+/// Si se cancela la ejecucion, se escribe el archivo json con la mejor solucion encontrada hasta ese momento y el estado
+/// interrumpida, sin aplicar el barrido, sin registrarla en el indice ni en la base y sin generar las graficas.
+/// Si se solicitan puntos de control, el estado del recocido se escribe periodicamente y el archivo se elimina unicamente despues de registrar la semilla en la base y en el indice.
+/// Al continuar desde un punto de control el resultado es el mismo que sin la interrupcion, aunque la traza, las graficas
+/// y la detencion temprana unicamente consideran los lotes calculados despues de reanudar.
+/// Regresa el resultado de la semilla para poder resumir un intervalo de semillas.
 /// 
-fn lanzar_tsp(semilla: i64, g: Grafica, numeros: Vec<i64>, cadena: String, svg: bool) -> std::io::Result<()> {
-    let g2 = g.clone();
-    let mut tsp = Tsp::new(20000.0, g, numeros, semilla);
-    let _ = tsp.generar_primer_solucion();
-    let _ = tsp.aceptacion_por_umbrales();
-    
-    let ruta = format!("resultados/{}_semilla_{}_{}.txt", cadena,semilla, Local::now().format("%Y-%m-%d_%H-%M-%S") );
-    let mut file = File::create(ruta)?;
+fn lanzar_tsp(semilla: i64, instancia: &Instancia, opciones: &Opciones, registros: &Registros) -> std::io::Result<Resultado> {
+    let inicio = Instant::now();
+    let nombre = format!("{}_semilla_{}_{}", opciones.cadena, semilla, Local::now().format("%Y-%m-%d_%H-%M-%S"));
+    let ruta_punto_control = format!("resultados/{}_semilla_{}_punto_control.json", opciones.cadena, semilla);
+    let punto = if opciones.continuar { PuntoControl::leer_json(&ruta_punto_control).ok() } else { None };
+    let reanudado = punto.is_some();
+    let mut tsp = match punto {
+        Some(punto) => {
+            let mut ciudades = punto.solucion_actual.clone();
+            let mut esperadas = instancia.ciudades.clone();
+            ciudades.sort();
+            esperadas.sort();
+            if ciudades != esperadas || punto.parametros != opciones.parametros {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("El punto de control {} no corresponde a la instancia o a los parametros", ruta_punto_control)));
+            }
+            println!("Semilla {} continua desde el lote {}", semilla, punto.lotes);
+            Tsp::desde_punto_control(punto, instancia)
+        }
+        None => Tsp::con_parametros(opciones.parametros.clone(), instancia, semilla),
+    };
+    if let Some(cada) = opciones.punto_control {
+        let ruta = ruta_punto_control.clone();
+        tsp.guardar_puntos_control(cada, move |p| {
+            if let Err(error) = p.escribir_json(&ruta) {
+                eprintln!("Semilla {} no se pudo escribir el punto de control: {}", semilla, error);
+            }
+        });
+    }
+    tsp.establecer_cancelacion(opciones.cancelacion.clone());
+    if let Some(cada) = opciones.progreso {
+        tsp.reportar_progreso(cada, move |p| {
+            println!(
+                "Semilla {} Lote {} Temperatura {:.6} Actual {:.6} Mejor {:.6} Aceptacion {:.3} Aceptadas {}",
+                semilla, p.lotes, p.temperatura, p.costo_actual, p.mejor, p.tasa_aceptacion, p.aceptadas
+            );
+        });
+    }
+    if let Some(paciencia) = opciones.detener {
+        tsp.agregar_observador(DetencionTemprana::new(paciencia));
+    }
+    tsp.establecer_traza(match opciones.traza {
+        Some(extension) => Traza::archivo(&format!("resultados/{}_traza.{}", nombre, extension))?,
+        None if opciones.limite_traza == 0 => Traza::Apagada,
+        None => Traza::Memoria(Decimador::new(opciones.limite_traza)),
+    });
+    let instantaneas = Arc::new(Mutex::new(Instantaneas::new(opciones.animacion)));
+    if opciones.animacion > 0 {
+        tsp.agregar_observador(Arc::clone(&instantaneas));
+    }
+    if reanudado {
+        tsp.reanudar_recocido();
+    } else {
+        tsp.generar_primer_solucion();
+        tsp.aceptacion_por_umbrales();
+    }
+    let tiempo_recocido = inicio.elapsed().as_secs_f64();
+    if tsp.interrumpido() {
+        println!("Semilla {} interrumpida, se conserva la mejor solucion encontrada", semilla);
+    } else if tsp.detenido() {
+        println!("Semilla {} detenida tras {} lotes sin mejora", semilla, opciones.detener.unwrap_or(0));
+    }
+    let (muestra, paso) = {
+        let mut traza = tsp.tomar_traza();
+        traza.cerrar()?;
+        traza.muestra(PUNTOS_MUESTRA)?
+    };
 
+    let mut resultado = Resultado::desde_tsp(&tsp, tiempo_recocido);
+    resultado.tiempo = inicio.elapsed().as_secs_f64();
+    resultado.curva_mejor = curva_mejor(&muestra, PUNTOS_CURVA);
+    let estado = resultado.estado;
 
-    let mut tsp_mejor = Tsp::new(10000.0, g2, tsp.mejor_solucion_arr, semilla);
+    println!(
+        "Valor {} Semilla {} Longitud {:.3} km Factor de penalizacion {} Aristas penalizadas {} Factible {}",
+        resultado.costo, semilla, resultado.longitud / 1000.0, resultado.factor_penalizacion, resultado.aristas_penalizadas, if resultado.factible { "si" } else { "no" }
+    );
 
-    let _  = tsp_mejor.barrido();
-    
-    println!("Valor {} Semilla {}", tsp_mejor.calcular_solucion(), semilla);
-    let contenido = format!("Soluciones Aceptadas: {:?}\n Solucion Actual {:?}\n Valor: {}\n ArregloMs: {:?} \n Mejor solucion {}\n Semilla: {}", tsp.soluciones_aceptadas, tsp.solucion_actual, tsp.peso_solucion_actual,tsp_mejor.solucion_actual , tsp_mejor.peso_solucion_actual, semilla);
-    file.write_all(contenido.as_bytes())?;
+    let ruta = format!("resultados/{}.json", nombre);
+    resultado.escribir_json(&ruta)?;
+    Evaluacion::new(tsp.get_instancia(), &resultado.solucion).escribir_csv(&format!("resultados/{}_aristas.csv", nombre))?;
+    if estado == Estado::Interrumpida {
+        return Ok(resultado);
+    }
+    if let Some(base) = &registros.base {
+        let traza = if registros.base_traza { Some(muestra.as_slice()) } else { None };
+        base.lock().unwrap().registrar(&resultado, traza).map_err(std::io::Error::other)?;
+    }
+    registros.indice.lock().unwrap().registrar(&resultado, &ruta)?;
+    if opciones.punto_control.is_some() || reanudado {
+        let _ = fs::remove_file(&ruta_punto_control);
+    }
 
-    let nombre_svg = format!("{}_semilla_{}", cadena, semilla);
-    if svg {
-        generar(tsp.soluciones_aceptadas,tsp.soluciones_para_svg, nombre_svg);
+    let nombre_svg = format!("{}_semilla_{}", opciones.cadena, semilla);
+    if opciones.convergencia {
+        let titulo = format!("{} semilla {}", opciones.instancia, semilla);
+        generar_convergencia(&muestra, paso, &tsp.historial_lotes, &titulo, &format!("{}_convergencia", nombre_svg))
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+    }
+    if opciones.mapa {
+        generar_mapa(tsp.get_instancia(), &resultado.solucion, opciones.proyeccion, format!("{}_mapa", nombre_svg));
+    }
+    if opciones.animacion > 0 {
+        let mut cuadros: Vec<(String, Vec<i64>)> = instantaneas
+            .lock()
+            .unwrap()
+            .instantaneas
+            .iter()
+            .map(|i| (format!("Aceptada {} Costo {:.6}", i.aceptada, i.costo), i.recorrido.clone()))
+            .collect();
+        cuadros.push((format!("Barrido Costo {:.6}", resultado.costo), resultado.solucion.clone()));
+        let db = tsp.get_instancia();
+        if opciones.cuadros {
+            generar_cuadros(db, &cuadros, opciones.proyeccion, format!("{}_cuadro", nombre_svg));
+        } else {
+            generar_animacion(db, &cuadros, opciones.proyeccion, 0.5, format!("{}_animacion", nombre_svg));
+        }
+    }
+    if opciones.svg {
+        let mut opciones_svg = opciones.opciones_svg.clone();
+        opciones_svg.titulo.get_or_insert_with(|| format!("{} semilla {}", opciones.instancia, semilla));
+        generar(&muestra, paso, nombre_svg, &opciones_svg);
     }
-    Ok(())
+    Ok(resultado)
     
 }
 
+/// Funcion encargada de repartir un conjunto de semillas entre varios hilos.
+///
+/// Los parametros estan dados por:
+/// - semillas:`&[i64]` : Semillas a ejecutar.
+/// - num_hilos:usize : Cantidad de hilos que ejecutaran las semillas.
+/// - instancia:&Instancia : Instancia compartida por referencia entre todas las ejecuciones.
+/// - opciones:&Opciones : Opciones de ejecucion de cada semilla.
+/// - registros:&Registros : Indice y base de resultados.
+///
+/// Cada hilo toma la siguiente semilla disponible a partir de un contador atomico, de esta manera
+/// los hilos que terminan antes toman mas trabajo. Si se reanuda, las semillas que ya se completaron con la misma
+/// instancia y parametros se leen de su archivo en lugar de recalcularse. Regresa los resultados de las semillas
+/// completadas y los errores de las semillas que no pudieron completarse.
+///
+/// Al cancelarse la ejecucion los hilos dejan de tomar semillas nuevas y las semillas en curso terminan como interrumpidas.
+///
+/// Si se solicita el progreso, al terminar cada semilla se imprimen las semillas completadas y restantes
+/// y el tiempo estimado para terminar, calculado a partir de las semillas que se han ejecutado.
+///
+fn ejecutar_semillas(semillas: &[i64], num_hilos: usize, instancia: &Instancia, opciones: &Opciones, registros: &Registros) -> (Vec<Resultado>, Vec<(i64, std::io::Error)>) {
+    let siguiente = AtomicUsize::new(0);
+    let resultados = Mutex::new(Vec::new());
+    let errores = Mutex::new(Vec::new());
+    let hash_i = &instancia.hash;
+    let hash_p = hash_parametros(&opciones.parametros);
+    let inicio = Instant::now();
+    let completadas = AtomicUsize::new(0);
+    let ejecutadas = AtomicUsize::new(0);
+    let reportar = |ejecutada: bool| {
+        let completadas = completadas.fetch_add(1, Ordering::Relaxed) + 1;
+        let ejecutadas = if ejecutada { ejecutadas.fetch_add(1, Ordering::Relaxed) + 1 } else { ejecutadas.load(Ordering::Relaxed) };
+        if opciones.progreso.is_none() {
+            return;
+        }
+        let restantes = semillas.len() - completadas;
+        let estimado = if ejecutadas > 0 {
+            formatear_duracion(inicio.elapsed().as_secs_f64() * restantes as f64 / ejecutadas as f64)
+        } else {
+            "-".to_string()
+        };
+        println!("Semillas completadas {}/{} Restantes {} Tiempo estimado {}", completadas, semillas.len(), restantes, estimado);
+    };
+
+    thread::scope(|s| {
+        for _ in 0..num_hilos {
+            s.spawn(|| {
+                while let Some(&semilla) = semillas.get(siguiente.fetch_add(1, Ordering::Relaxed)) {
+                    if opciones.cancelacion.cancelado() {
+                        break;
+                    }
+                    if opciones.reanudar {
+                        let ruta = registros.indice.lock().unwrap().buscar(hash_i, &hash_p, semilla).map(|r| r.to_string());
+                        if let Some(mut resultado) = ruta.and_then(|r| Resultado::leer_json(&r).ok()).filter(|r| instancia.es_permutacion(&r.solucion)) {
+                            resultado.completar(instancia);
+                            println!("Semilla {} ya completada, se omite", semilla);
+                            resultados.lock().unwrap().push(resultado);
+                            reportar(false);
+                            continue;
+                        }
+                    }
+                    match lanzar_tsp(semilla, instancia, opciones, registros) {
+                        Ok(resultado) => resultados.lock().unwrap().push(resultado),
+                        Err(error) => errores.lock().unwrap().push((semilla, error)),
+                    }
+                    reportar(true);
+                }
+            });
+        }
+    });
+
+    (resultados.into_inner().unwrap(), errores.into_inner().unwrap())
+}
+
+/// Funcion encargada de crear el token de cancelacion que se cancela al recibir Ctrl-C.
+///
+/// La primera senal cancela el token para que cada semilla en curso escriba su mejor solucion y termine,
+/// una segunda senal termina el proceso de inmediato.
+///
+fn cancelacion_ctrl_c() -> Cancelacion {
+    let cancelacion = Cancelacion::new();
+    let copia = cancelacion.clone();
+    ctrlc::set_handler(move || {
+        if copia.cancelado() {
+            std::process::exit(130);
+        }
+        eprintln!("Cancelando, se escribira la mejor solucion de cada semilla en curso (Ctrl-C de nuevo para salir)");
+        copia.cancelar();
+    }).expect("No se pudo registrar el manejador de Ctrl-C");
+    cancelacion
+}
+
+/// Funcion encargada de dar formato de horas, minutos y segundos a una duracion en segundos.
+///
+fn formatear_duracion(segundos: f64) -> String {
+    let segundos = segundos.max(0.0).round() as u64;
+    format!("{:02}:{:02}:{:02}", segundos / 3600, (segundos / 60) % 60, segundos % 60)
+}
+
+/// Funcion encargada de leer la instancia de un archivo.
+///
+/// Los archivos `.csv` se leen como coordenadas `id,latitud,longitud`, los archivos con la seccion `NODE_COORD_SECTION` como TSPLIB
+/// y el resto como una lista de ciudades separadas por comas, cuyas distancias se cargan de la base de datos `tsp.db`
+/// o del cache en `DIRECTORIO_CACHE` si la instancia ya se habia cargado con la misma base de datos.
+///
+fn leer_instancia(ruta: &str) -> Instancia {
+    if ruta.ends_with(".csv") {
+        return Instancia::desde_csv(ruta, Metrica::Geografica).expect("No se pudo leer el csv de coordenadas");
+    }
+    let contenido = fs::read_to_string(ruta).expect("No se pudo leer el archivo de ciudades");
+    if contenido.contains("NODE_COORD_SECTION") {
+        return Instancia::desde_tsplib(ruta).expect("No se pudo leer el archivo TSPLIB");
+    }
+    let numeros: Vec<i64> = contenido.trim().split(',').map(|s| s.trim().parse::<i64>().expect("Error al convertir el numero")).collect();
+    Instancia::cargar_db_cache(ruta, &numeros, DIRECTORIO_CACHE).expect("No se pudo cargar la base de datos")
+}
+
+/// Funcion encargada de obtener la proyeccion solicitada en los argumentos.
+///
+fn proyeccion_solicitada(args: &[String]) -> Proyeccion {
+    if args.iter().any(|a| a == "--mercator") {
+        Proyeccion::Mercator
+    } else {
+        Proyeccion::Equirectangular
+    }
+}
+
+/// Funcion encargada de obtener el formato del archivo de traza solicitado en los argumentos.
+///
+/// Con `--traza` la traza se escribe en csv y con `--traza-bin` en binario.
+///
+fn destino_traza(args: &[String]) -> Option<&'static str> {
+    if args.iter().any(|a| a == "--traza-bin") {
+        Some("bin")
+    } else if args.iter().any(|a| a == "--traza") {
+        Some("csv")
+    } else {
+        None
+    }
+}
+
+/// Funcion encargada de obtener el valor de una opcion de la linea de comandos.
+///
+/// Busca la opcion dentro de los argumentos y regresa el argumento que le sigue, en caso de existir.
+///
+fn valor_opcion(args: &[String], opcion: &str) -> Option<String> {
+    args.iter().position(|a| a == opcion).and_then(|i| args.get(i + 1).cloned())
+}
+
+/// Funcion encargada de obtener las opciones del svg de soluciones aceptadas a partir de los argumentos.
+///
+/// Las opciones que no se den toman su valor por omision.
+///
+fn opciones_svg(args: &[String]) -> OpcionesSvg {
+    let numero = |opcion: &str| valor_opcion(args, opcion).map(|v| v.parse::<f64>().unwrap_or_else(|_| panic!("Error al parsear {}", opcion)));
+    let omision = OpcionesSvg::default();
+    OpcionesSvg {
+        width: numero("--ancho").unwrap_or(omision.width),
+        height: numero("--alto").unwrap_or(omision.height),
+        margin: numero("--margen").unwrap_or(omision.margin),
+        tolerancia: numero("--tolerancia").unwrap_or(omision.tolerancia),
+        max_puntos: valor_opcion(args, "--max-puntos").map(|v| v.parse::<usize>().expect("Error al parsear --max-puntos")),
+        color_linea: valor_opcion(args, "--color-linea").unwrap_or(omision.color_linea),
+        color_mejora: valor_opcion(args, "--color-mejora").unwrap_or(omision.color_mejora),
+        color_fondo: valor_opcion(args, "--color-fondo").unwrap_or(omision.color_fondo),
+        titulo: valor_opcion(args, "--titulo"),
+        escala_log: args.iter().any(|a| a == "--log"),
+        desde: valor_opcion(args, "--desde").map(|v| v.parse::<usize>().expect("Error al parsear --desde")),
+        hasta: valor_opcion(args, "--hasta").map(|v| v.parse::<usize>().expect("Error al parsear --hasta")),
+        acercamiento: numero("--acercamiento"),
+    }
+}
+
+/// Funcion encargada de generar una instancia aleatoria a partir de los argumentos y escribirla en un archivo.
+///
+/// Con `db` se eligen ciudades de la base de datos, opcionalmente conexas (`--conexas`) y dentro de una region (`--caja`),
+/// y se escriben como lista de ciudades o como TSPLIB `GEO` con `--tsplib`. Con `uniforme` y `agrupada` se generan
+/// coordenadas en el plano, escritas como TSPLIB `EUC_2D`.
+///
+fn generar_instancia(args: &[String]) {
+    let n = args[3].parse::<usize>().expect("Error al parsear n");
+    let semilla = args[4].parse::<i64>().expect("Error al parsear semilla");
+    let salida = &args[5];
+    let nombre = salida.rsplit('/').next().unwrap_or(salida).trim_end_matches(".tsp");
+    match args[2].as_str() {
+        "db" => {
+            let caja = valor_opcion(args, "--caja").map(|v| {
+                let limites: Vec<f64> = v.split(',').map(|s| s.trim().parse::<f64>().expect("Error al parsear --caja")).collect();
+                assert_eq!(limites.len(), 4, "--caja recibe lat_min,lat_max,lon_min,lon_max");
+                Caja { lat_min: limites[0], lat_max: limites[1], lon_min: limites[2], lon_max: limites[3] }
+            });
+            let mut db = CityDB::new(&Vec::new());
+            db.cargar_datos().expect("No se pudo cargar la base de datos");
+            let conexas = args.iter().any(|a| a == "--conexas");
+            let ciudades = ciudades_aleatorias(&db, n, semilla, caja, conexas).expect("No hay suficientes ciudades que cumplan las restricciones");
+            if args.iter().any(|a| a == "--tsplib") {
+                let coordenadas: Vec<(f64, f64)> = ciudades.iter().map(|c| db.coordenadas[*c as usize]).collect();
+                escribir_tsplib(salida, nombre, &ciudades, &coordenadas, Metrica::Geografica)
+            } else {
+                escribir_lista(salida, &ciudades)
+            }
+        }
+        tipo => {
+            let distribucion = match tipo {
+                "uniforme" => Distribucion::Uniforme,
+                "agrupada" => Distribucion::Agrupada(valor_opcion(args, "--grupos").map(|v| v.parse::<usize>().expect("Error al parsear --grupos")).unwrap_or(5)),
+                _ => panic!("Tipo de instancia desconocido: {}", tipo),
+            };
+            let coordenadas = coordenadas_sinteticas(n, distribucion, semilla);
+            let ciudades: Vec<i64> = (1..=n as i64).collect();
+            escribir_tsplib(salida, nombre, &ciudades, &coordenadas, Metrica::Euclidiana)
+        }
+    }
+    .expect("No se pudo escribir la instancia");
+    println!("Instancia escrita en {}", salida);
+}
+
+/// Funcion encargada de leer un recorrido de un archivo.
+///
+/// Los archivos `.json` se leen como el resultado de una semilla y el resto como una lista de ciudades separadas por comas.
+///
+fn leer_recorrido(ruta: &str) -> Vec<i64> {
+    if ruta.ends_with(".json") {
+        return Resultado::leer_json(ruta).expect("No se pudo leer el resultado").solucion;
+    }
+    let contenido = fs::read_to_string(ruta).expect("No se pudo leer el archivo del recorrido");
+    contenido.trim().split(',').map(|s| s.trim().parse::<i64>().expect("Error al convertir el numero")).collect()
+}
+
+/// Funcion encargada de leer un recorrido de un archivo y verificar que sea una permutacion de las ciudades de la instancia.
+///
+/// En otro caso imprime el error y regresa `None`.
+///
+fn leer_recorrido_instancia(instancia: &Instancia, ruta: &str) -> Option<Vec<i64>> {
+    let recorrido = leer_recorrido(ruta);
+    if !instancia.es_permutacion(&recorrido) {
+        eprintln!("El recorrido de {} no es una permutacion de las ciudades de la instancia {}", ruta, instancia.nombre);
+        return None;
+    }
+    Some(recorrido)
+}
+
+/// Funcion encargada de imprimir el analisis de conectividad de una instancia y, en caso de darse, las aristas penalizadas de un recorrido.
+///
+fn imprimir_analisis(instancia: &Instancia, recorrido: Option<Vec<i64>>) {
+    let analisis = Analisis::new(instancia);
+    println!("Ciudades: {}", analisis.ciudades);
+    println!("Normalizador: {} Distancia maxima: {}", instancia.normalizador, instancia.distancia_maxima);
+    println!("Aristas reales: {} Densidad: {:.4}", analisis.aristas, analisis.densidad);
+    let tamanos: Vec<String> = analisis.componentes.iter().map(|c| c.len().to_string()).collect();
+    println!("Componentes conexas: {} Tamaños: {}", analisis.componentes.len(), tamanos.join(","));
+    for componente in analisis.componentes.iter().skip(1) {
+        println!("  Componente: {}", componente.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(","));
+    }
+    println!("Distribucion de grados:");
+    for (grado, cantidad) in analisis.distribucion_grados().iter().enumerate().filter(|(_, c)| **c > 0) {
+        println!("  Grado {:>4}: {} ciudades", grado, cantidad);
+    }
+    if analisis.hamiltoniano_plausible() {
+        println!("Camino hamiltoniano con aristas reales: plausible");
+    } else {
+        println!("Camino hamiltoniano con aristas reales: imposible");
+        for obstaculo in analisis.obstaculos_hamiltoniano() {
+            println!("  {}", obstaculo);
+        }
+    }
+    if let Some(recorrido) = recorrido {
+        let penalizadas = aristas_penalizadas(instancia, &recorrido);
+        println!("Aristas penalizadas del recorrido: {} de {}", penalizadas.len(), recorrido.len().saturating_sub(1));
+    }
+}
+
+/// Funcion encargada de imprimir la evaluacion de un recorrido arista por arista junto con sus totales.
+///
+fn imprimir_evaluacion(evaluacion: &Evaluacion) {
+    for arista in &evaluacion.aristas {
+        if arista.penalizada {
+            println!("{} -> {} : penalizada {} = {} * {}", arista.origen, arista.destino, arista.peso, arista.peso / evaluacion.distancia_maxima, evaluacion.distancia_maxima);
+        } else {
+            println!("{} -> {} : {}", arista.origen, arista.destino, arista.peso);
+        }
+    }
+    imprimir_totales(evaluacion);
+}
+
+/// Funcion encargada de imprimir los totales de la evaluacion de un recorrido.
+///
+/// Imprime la longitud sin normalizar en metros y kilometros, el factor de penalizacion de las aristas que no existen,
+/// el normalizador, el costo normalizado, la cantidad de aristas penalizadas y si el recorrido es factible.
+///
+fn imprimir_totales(evaluacion: &Evaluacion) {
+    println!("Longitud: {} m ({:.3} km) Factor de penalizacion: {}", evaluacion.longitud, evaluacion.longitud / 1000.0, evaluacion.distancia_maxima);
+    println!("Normalizador: {} Costo: {} Aristas penalizadas: {} Factible: {}", evaluacion.normalizador, evaluacion.costo, evaluacion.penalizadas, if evaluacion.factible() { "si" } else { "no" });
+}
+
 fn main(){
     let args: Vec<String> = env::args().collect();
     if args.len() > 1 && args[1] == "-s" {
-        let contenido = fs::read_to_string(&args[2]);
-        
-        let numeros: Vec<f64> = contenido.expect("No es un entero").trim().split(',').map(|s| s.trim().parse::<f64>().expect("Error al convertir el numero")).collect();
-        let rojos:Vec<(f64, bool)> = Vec::new();
-        generar(numeros, rojos, args[3].clone());
+        let (datos, paso) = if args[2].ends_with(".csv") || args[2].ends_with(".bin") {
+            leer_traza(&args[2], PUNTOS_MUESTRA).expect("No se pudo leer la traza")
+        } else {
+            let contenido = fs::read_to_string(&args[2]);
+            let numeros: Vec<(f64, bool)> = contenido.expect("No es un entero").trim().split(',').map(|s| (s.trim().parse::<f64>().expect("Error al convertir el numero"), false)).collect();
+            (numeros, 1)
+        };
+        generar(&datos, paso, args[3].clone(), &opciones_svg(&args));
         return;
 
     } else if args.len() > 1 && args[1] == "-b" {
-        let contenido = fs::read_to_string(&args[2]);
-
-        let numeros: Vec<i64> = contenido.expect("No es un entero").trim().split(',').map(|s| s.trim().parse::<i64>().expect("Error al convertir el numero")).collect();
-        
-        let mut cities = CityDB::new(&numeros);
-        let _ = cities.cargar_datos();
-        let g = Grafica::new(cities);
-        let mut tsp = Tsp::new(50000.0, g, numeros, 0);
-        let _ = tsp.barrido();
+        let instancia = leer_instancia(&args[2]);
+        let mut tsp = Tsp::new(50000.0, &instancia, 0);
+        tsp.barrido();
         println!("Resultado: {}", tsp.calcular_solucion());
+        imprimir_totales(&Evaluacion::new(tsp.get_instancia(), &tsp.solucion_actual));
         return;
         
     } else if args.len() > 1 && args[1] == "-e" {
-        let contenido = fs::read_to_string(&args[2]);
-        let numeros: Vec<i64> = contenido.expect("No es un entero").trim().split(',').map(|s| s.trim().parse::<i64>().expect("Error al convertir el numero")).collect();
-        let mut cities = CityDB::new(&numeros);
-        let _ = cities.cargar_datos();
-        let g = Grafica::new(cities);
-        let mut tsp = Tsp::new(50000.0, g, numeros, 0);
+        let instancia = leer_instancia(&args[2]);
+        let mut tsp = Tsp::new(50000.0, &instancia, 0);
+        if let Some(ruta) = args.get(3) {
+            let Some(recorrido) = leer_recorrido_instancia(tsp.get_instancia(), ruta) else {
+                return;
+            };
+            tsp.establecer_recorrido(recorrido);
+        }
         println!("Resultado: {}", tsp.calcular_solucion());
+        imprimir_evaluacion(&Evaluacion::new(tsp.get_instancia(), &tsp.solucion_actual));
+        return;
+    } else if args.len() > 3 && args[1] == "-m" {
+        let instancia = leer_instancia(&args[2]);
+        generar_mapa(&instancia, &instancia.ciudades, proyeccion_solicitada(&args[4..]), args[3].clone());
+        return;
+    } else if args.len() > 3 && args[1] == "-q" {
+        let instancia = leer_instancia(&args[2]);
+        let k = args[3].parse::<usize>().expect("Error al parsear k");
+        let ruta = valor_opcion(&args, "--db").unwrap_or_else(|| RUTA_BASE_RESULTADOS.to_string());
+        let base = BaseResultados::abrir(&ruta).expect("No se pudo abrir la base de resultados");
+        let mejores = base.mejores(&instancia.hash, k).expect("No se pudo consultar la base de resultados");
+        for (i, ejecucion) in mejores.iter().enumerate() {
+            println!("{}. Costo {} Semilla {} Tiempo {:.2}s Fecha {} Instancia {}", i + 1, ejecucion.costo, ejecucion.semilla, ejecucion.tiempo, ejecucion.fecha, ejecucion.instancia);
+        }
+        if let Some(mejor) = mejores.first() {
+            let recorrido = base.recorrido(mejor.id).expect("No se pudo consultar el recorrido");
+            println!("Mejor recorrido: {}", recorrido.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(","));
+        }
+        return;
+    } else if args.len() > 3 && args[1] == "-c" {
+        let configuraciones: Vec<_> = args[3..]
+            .iter()
+            .map(|ruta| leer_curvas(ruta).expect("No se pudo leer el archivo de curvas"))
+            .collect();
+        generar_comparacion(&configuraciones, &args[2]).expect("No se pudo generar la grafica de comparacion");
+        return;
+    } else if args.len() > 2 && args[1] == "-a" {
+        let instancia = leer_instancia(&args[2]);
+        let recorrido = match args.get(3) {
+            Some(ruta) => match leer_recorrido_instancia(&instancia, ruta) {
+                Some(recorrido) => Some(recorrido),
+                None => return,
+            },
+            None => None,
+        };
+        imprimir_analisis(&instancia, recorrido);
+        return;
+    } else if args.len() > 5 && args[1] == "-g" {
+        generar_instancia(&args);
         return;
     }else if args.len() < 5 {
         println!("Los argumentos son: <tipo de consulta> <file> <1er semilla> <2da semilla?> <nombre inicial archivo salida> <-s?>");
         return;
     }
 
-    let contenido = fs::read_to_string(&args[2]);
-
-    let numeros: Vec<i64> = contenido.expect("No es un entero").trim().split(',').map(|s| s.parse::<i64>().expect("Error al convertir el numero")).collect();
-
-    let mut cities = CityDB::new(&numeros);
-
-    let _ = cities.cargar_datos();
+    let instancia = leer_instancia(&args[2]);
 
-    let g = Grafica::new(cities);
-
-    let mut svg = false;
     if args[1] == "-o" {
-        if args.len() > 5 && args[5] == "-s" {
-            svg = true;
-        }
+        let opciones = Opciones {
+            instancia: args[2].clone(),
+            cadena: args[4].clone(),
+            svg: args.iter().skip(5).any(|a| a == "-s"),
+            opciones_svg: opciones_svg(&args),
+            traza: destino_traza(&args[5..]),
+            limite_traza: valor_opcion(&args, "--limite-traza").map(|n| n.parse::<usize>().expect("Error al parsear el limite de la traza")).unwrap_or(LIMITE_TRAZA),
+            convergencia: args.iter().skip(5).any(|a| a == "--convergencia"),
+            mapa: args.iter().skip(5).any(|a| a == "--mapa"),
+            animacion: valor_opcion(&args, "--animacion").map(|n| n.parse::<usize>().expect("Error al parsear el numero de instantaneas")).unwrap_or(0),
+            cuadros: args.iter().skip(5).any(|a| a == "--cuadros"),
+            proyeccion: proyeccion_solicitada(&args[5..]),
+            progreso: valor_opcion(&args, "--progreso").map(|n| n.parse::<usize>().expect("Error al parsear el numero de lotes del progreso")),
+            detener: valor_opcion(&args, "--detener").map(|n| n.parse::<usize>().expect("Error al parsear el numero de lotes sin mejora")),
+            reanudar: false,
+            punto_control: valor_opcion(&args, "--punto-control").map(|n| n.parse::<usize>().expect("Error al parsear el numero de lotes entre puntos de control")),
+            continuar: args.iter().skip(5).any(|a| a == "--continuar"),
+            cancelacion: cancelacion_ctrl_c(),
+            parametros: Parametros::default(),
+        };
+        let registros = Registros::new(&args);
         let semilla = args[3].parse::<i64>().expect("Error al parsear semilla");
-        lanzar_tsp(semilla, g, numeros, args[4].clone(), svg).unwrap();
+        lanzar_tsp(semilla, &instancia, &opciones, &registros).unwrap();
     } else if args[1] == "-i" {
-        if args.len() > 6 && args[6] == "-s" {
-            svg = true;
-        }
-        let num_threads = 6;
+        let num_hilos = match valor_opcion(&args, "-t") {
+            Some(valor) => valor.parse::<usize>().expect("Error al parsear el numero de hilos"),
+            None => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        };
         let semilla1 = args[3].parse::<i64>().expect("Error al parsear semilla");
         let semilla2 = args[4].parse::<i64>().expect("Error al parsear semilla");
-        let semillas_q: VecDeque<i64> = (semilla1..semilla2).collect();
-
-        let cola = Arc::new(Mutex::new(semillas_q));
-        let mut handles = Vec::new();
-
-        for _i in 0..num_threads {
-            let cola_2 = Arc::clone(&cola);
-            let g_2 = g.clone();
-            let numeros_2 = numeros.clone();
-            let cadena = args.get(5).cloned().unwrap_or_else(||"tsp_".to_string());
-
-            let handle = thread::spawn(move || {
-                loop {
-                    let semilla_opt;
-                    {
-                        let mut cola_block = cola_2.lock().unwrap();
-                        semilla_opt = cola_block.pop_front();
-                    }
-                    if let Some(semilla) = semilla_opt {
-                        let _ = lanzar_tsp(semilla, g_2.clone(), numeros_2.clone(), cadena.clone(), svg);
-                    }else {
-                        break;
-                    }
-                }
-            });
-            handles.push(handle);
-            
+        let semillas: Vec<i64> = (semilla1..semilla2).collect();
+        let nombrada = args.get(5).filter(|c| !c.starts_with('-'));
+        let cadena = nombrada.cloned().unwrap_or_else(||"tsp_".to_string());
+        let inicio = if nombrada.is_some() { 6 } else { 5 };
+        let opciones = Opciones {
+            instancia: args[2].clone(),
+            cadena: cadena.clone(),
+            svg: args.iter().skip(inicio).any(|a| a == "-s"),
+            opciones_svg: opciones_svg(&args),
+            traza: destino_traza(args.get(inicio..).unwrap_or(&[])),
+            limite_traza: valor_opcion(&args, "--limite-traza").map(|n| n.parse::<usize>().expect("Error al parsear el limite de la traza")).unwrap_or(LIMITE_TRAZA),
+            convergencia: args.iter().skip(inicio).any(|a| a == "--convergencia"),
+            mapa: args.iter().skip(inicio).any(|a| a == "--mapa"),
+            animacion: valor_opcion(&args, "--animacion").map(|n| n.parse::<usize>().expect("Error al parsear el numero de instantaneas")).unwrap_or(0),
+            cuadros: args.iter().skip(inicio).any(|a| a == "--cuadros"),
+            proyeccion: proyeccion_solicitada(args.get(inicio..).unwrap_or(&[])),
+            progreso: valor_opcion(&args, "--progreso").map(|n| n.parse::<usize>().expect("Error al parsear el numero de lotes del progreso")),
+            detener: valor_opcion(&args, "--detener").map(|n| n.parse::<usize>().expect("Error al parsear el numero de lotes sin mejora")),
+            reanudar: !args.iter().skip(inicio).any(|a| a == "--recalcular"),
+            punto_control: valor_opcion(&args, "--punto-control").map(|n| n.parse::<usize>().expect("Error al parsear el numero de lotes entre puntos de control")),
+            continuar: args.iter().skip(inicio).any(|a| a == "--continuar"),
+            cancelacion: cancelacion_ctrl_c(),
+            parametros: Parametros::default(),
+        };
+        let registros = Registros::new(&args);
+
+        let (mut resultados, mut errores) = ejecutar_semillas(&semillas, num_hilos.max(1), &instancia, &opciones, &registros);
+
+        resultados.sort_by_key(|r| r.semilla);
+        let interrumpidas: Vec<i64> = resultados.iter().filter(|r| r.estado == Estado::Interrumpida).map(|r| r.semilla).collect();
+        resultados.retain(|r| r.estado != Estado::Interrumpida);
+        if opciones.cancelacion.cancelado() {
+            println!("Ejecucion cancelada, semillas interrumpidas: {:?}", interrumpidas);
+        }
+        if !errores.is_empty() {
+            errores.sort_by_key(|(semilla, _)| *semilla);
+            eprintln!("{} semillas terminaron con error:", errores.len());
+            for (semilla, error) in errores {
+                eprintln!("  Semilla {}: {}", semilla, error);
+            }
         }
 
-        for handle in handles {
-            handle.join().unwrap();
+        if let Some(resumen) = Resumen::new(&resultados, 10) {
+            resumen.imprimir();
+            if let Err(error) = escribir_csv(&resultados, &format!("resultados/{}_semillas.csv", cadena)) {
+                eprintln!("No se pudo escribir el csv de semillas: {}", error);
+            }
+            if let Err(error) = resumen.escribir_json(&format!("resultados/{}_resumen.json", cadena)) {
+                eprintln!("No se pudo escribir el resumen: {}", error);
+            }
+            let ruta_curvas = format!("resultados/{}_curvas.csv", cadena);
+            match escribir_curvas(&resultados, &ruta_curvas) {
+                Err(error) => eprintln!("No se pudo escribir el csv de curvas: {}", error),
+                Ok(()) if args.iter().skip(inicio).any(|a| a == "--comparacion") => {
+                    let comparacion = leer_curvas(&ruta_curvas).and_then(|curvas| {
+                        generar_comparacion(&[curvas], &format!("{}_comparacion", cadena)).map_err(|e| std::io::Error::other(e.to_string()))
+                    });
+                    if let Err(error) = comparacion {
+                        eprintln!("No se pudo generar la grafica de comparacion: {}", error);
+                    }
+                }
+                Ok(()) => {}
+            }
         }
 
     } 
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use crate::instancia::Instancia;
+use svg::node::element::{Animate, Circle, Group, Line, Rectangle, Text};
+use svg::Document;
+
+/// Proyeccion utilizada para pasar latitud y longitud al plano.
+///
+/// - Equirectangular: La longitud y la latitud se usan directamente como x y y.
+/// - Mercator: La latitud se transforma con la proyeccion de Mercator, conservando los angulos.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Proyeccion {
+    Equirectangular,
+    Mercator,
+}
+
+impl Proyeccion {
+
+    /// Funcion encargada de proyectar una coordenada.
+    ///
+    /// Recibe la latitud y la longitud en grados y regresa la coordenada (x, y) en el plano, donde y crece hacia el norte.
+    ///
+    /// # Example
+    /// ```
+    /// let (x, y) = Proyeccion::Mercator.proyectar(19.43, -99.13);
+    /// ```
+    pub fn proyectar(&self, latitud: f64, longitud: f64) -> (f64, f64) {
+        match self {
+            Proyeccion::Equirectangular => (longitud, latitud),
+            Proyeccion::Mercator => {
+                let lat = latitud.clamp(-85.0, 85.0) * PI / 180.0;
+                (longitud, (PI / 4.0 + lat / 2.0).tan().ln() * 180.0 / PI)
+            }
+        }
+    }
+}
+
+/// Estructura correspondiente al mapa de un conjunto de ciudades.
+///
+/// Almacena la posicion en el svg de cada ciudad, de tal manera que cualquier recorrido sobre esas
+/// ciudades pueda dibujarse con la misma escala.
+///
+/// - width:f64 : Ancho del svg.
+/// - height:f64 : Alto del svg.
+/// - posiciones: Mapa de la ciudad a su posicion en el svg.
+///
+pub struct Mapa {
+    pub width: f64,
+    pub height: f64,
+    posiciones: HashMap<i64, (f64, f64)>,
+}
+
+impl Mapa {
+
+    /// Constructor del mapa
+    ///
+    /// Proyecta las coordenadas de cada ciudad y las escala para que ocupen el svg respetando los margenes,
+    /// conservando la proporcion entre los ejes.
+    ///
+    /// # Example
+    /// ```
+    /// let mapa = Mapa::new(&instancia, &ciudades, Proyeccion::Equirectangular, 1600.0, 1000.0, 60.0);
+    /// ```
+    pub fn new(instancia: &Instancia, ciudades: &[i64], proyeccion: Proyeccion, width: f64, height: f64, margin: f64) -> Self {
+        let proyectadas: Vec<(i64, (f64, f64))> = ciudades
+            .iter()
+            .map(|&c| {
+                let (lat, lon) = instancia.coordenada(c);
+                (c, proyeccion.proyectar(lat, lon))
+            })
+            .collect();
+
+        let (min_x, max_x, min_y, max_y) = proyectadas.iter().fold(
+            (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY),
+            |(a, b, c, d), (_, (x, y))| (a.min(*x), b.max(*x), c.min(*y), d.max(*y)),
+        );
+
+        let escala_x = (width - 2.0 * margin) / (max_x - min_x).max(f64::EPSILON);
+        let escala_y = (height - 2.0 * margin) / (max_y - min_y).max(f64::EPSILON);
+        let escala = escala_x.min(escala_y);
+        let desfase_x = (width - (max_x - min_x) * escala) / 2.0;
+        let desfase_y = (height - (max_y - min_y) * escala) / 2.0;
+
+        let posiciones = proyectadas
+            .into_iter()
+            .map(|(c, (x, y))| (c, ((x - min_x) * escala + desfase_x, height - ((y - min_y) * escala + desfase_y))))
+            .collect();
+
+        Mapa { width, height, posiciones }
+    }
+
+    /// Funcion encargada de obtener la posicion en el svg de una ciudad.
+    ///
+    pub fn posicion(&self, ciudad: i64) -> (f64, f64) {
+        self.posiciones[&ciudad]
+    }
+
+    /// Funcion encargada de generar el documento base del mapa.
+    ///
+    /// Contiene el fondo y las ciudades con su identificador, sobre el cual se dibujan las aristas.
+    ///
+    pub fn documento(&self) -> Document {
+        let fondo = Rectangle::new()
+            .set("x", 0)
+            .set("y", 0)
+            .set("width", self.width)
+            .set("height", self.height)
+            .set("fill", "white");
+
+        let mut ciudades: Vec<(&i64, &(f64, f64))> = self.posiciones.iter().collect();
+        ciudades.sort_by_key(|(c, _)| **c);
+
+        let mut documento = Document::new().set("viewBox", (0, 0, self.width, self.height)).add(fondo);
+        for (ciudad, (x, y)) in ciudades {
+            documento = documento
+                .add(Circle::new()
+                    .set("cx", redondear(*x))
+                    .set("cy", redondear(*y))
+                    .set("r", 4)
+                    .set("fill", "black"))
+                .add(Text::new(ciudad.to_string())
+                    .set("x", redondear(*x + 5.0))
+                    .set("y", redondear(*y - 5.0))
+                    .set("font-size", 10)
+                    .set("fill", "dimgray"));
+        }
+        documento
+    }
+
+    /// Funcion encargada de generar la linea de una arista.
+    ///
+    /// Las aristas que existen en la base de datos se dibujan con una linea continua azul,
+    /// mientras que las aristas penalizadas se dibujan con una linea punteada roja.
+    ///
+    pub fn arista(&self, u: i64, v: i64, existe: bool) -> Line {
+        let (x1, y1) = self.posicion(u);
+        let (x2, y2) = self.posicion(v);
+        let linea = Line::new()
+            .set("x1", redondear(x1))
+            .set("y1", redondear(y1))
+            .set("x2", redondear(x2))
+            .set("y2", redondear(y2))
+            .set("stroke-width", 2);
+        if existe {
+            linea.set("stroke", "steelblue")
+        } else {
+            linea.set("stroke", "red").set("stroke-dasharray", "6,4")
+        }
+    }
+}
+
+/// Funcion encargada de redondear una coordenada a dos decimales.
+///
+fn redondear(valor: f64) -> f64 {
+    (valor * 100.0).round() / 100.0
+}
+
+/// Funcion encargada de generar el svg con el mapa de un recorrido.
+///
+/// Recibe como parametros lo siguiente:
+/// - instancia:&Instancia : Instancia con las coordenadas y las aristas existentes.
+/// - recorrido:`&[i64]` : Ciudades en el orden del recorrido.
+/// - proyeccion:Proyeccion : Proyeccion con la que se dibujan las ciudades.
+/// - nombre:String : Nombre del archivo svg a generar dentro de la carpeta svgs.
+///
+/// Dibuja cada ciudad con su identificador y las aristas en el orden del recorrido, distinguiendo las aristas penalizadas.
+///
+/// # Example
+/// ```
+/// generar_mapa(&instancia, &recorrido, Proyeccion::Equirectangular, "tsp_semilla_1_mapa".to_string());
+/// ```
+pub fn generar_mapa(instancia: &Instancia, recorrido: &[i64], proyeccion: Proyeccion, nombre: String) {
+    let ruta = format!("svgs/{}.svg", nombre);
+    if recorrido.is_empty() {
+        println!("No hay ciudades para generar el mapa.");
+        return;
+    }
+
+    let mapa = Mapa::new(instancia, recorrido, proyeccion, 1600.0, 1000.0, 60.0);
+    let mut documento = mapa.documento();
+    for par in recorrido.windows(2) {
+        documento = documento.add(mapa.arista(par[0], par[1], instancia.existe_arista(par[0], par[1])));
+    }
+
+    svg::save(ruta, &documento).expect("No se pudo guardar el SVG");
+}
+
+/// Funcion encargada de generar el grupo con las aristas de un recorrido.
+///
+/// Contiene las aristas del recorrido y un texto con la descripcion del cuadro.
+///
+fn cuadro(mapa: &Mapa, instancia: &Instancia, recorrido: &[i64], descripcion: &str) -> Group {
+    let mut grupo = Group::new().add(Text::new(descripcion.to_string())
+        .set("x", 20)
+        .set("y", 30)
+        .set("font-size", 20)
+        .set("fill", "black"));
+    for par in recorrido.windows(2) {
+        grupo = grupo.add(mapa.arista(par[0], par[1], instancia.existe_arista(par[0], par[1])));
+    }
+    grupo
+}
+
+/// Funcion encargada de generar la animacion de la evolucion de un recorrido.
+///
+/// Recibe como parametros lo siguiente:
+/// - instancia:&Instancia : Instancia con las coordenadas y las aristas existentes.
+/// - cuadros:`&[(String, Vec<i64>)]` : Descripcion y recorrido de cada cuadro de la animacion.
+/// - proyeccion:Proyeccion : Proyeccion con la que se dibujan las ciudades.
+/// - duracion:f64 : Duracion en segundos de cada cuadro.
+/// - nombre:String : Nombre del archivo svg a generar dentro de la carpeta svgs.
+///
+/// Genera un unico svg animado con SMIL, en el que cada cuadro es un grupo que unicamente es visible
+/// durante su intervalo de tiempo. La animacion se repite indefinidamente.
+///
+/// # Example
+/// ```
+/// generar_animacion(&instancia, &cuadros, Proyeccion::Equirectangular, 0.5, "tsp_semilla_1_animacion".to_string());
+/// ```
+pub fn generar_animacion(instancia: &Instancia, cuadros: &[(String, Vec<i64>)], proyeccion: Proyeccion, duracion: f64, nombre: String) {
+    let ruta = format!("svgs/{}.svg", nombre);
+    if cuadros.is_empty() {
+        println!("No hay cuadros para generar la animacion.");
+        return;
+    }
+
+    let n = cuadros.len();
+    let mapa = Mapa::new(instancia, &cuadros[0].1, proyeccion, 1600.0, 1000.0, 60.0);
+    let mut documento = mapa.documento();
+    for (i, (descripcion, recorrido)) in cuadros.iter().enumerate() {
+        let mut valores = vec![if i == 0 { "visible" } else { "hidden" }];
+        let mut tiempos = vec![0.0];
+        if i > 0 {
+            valores.push("visible");
+            tiempos.push(i as f64 / n as f64);
+        }
+        if i + 1 < n {
+            valores.push("hidden");
+            tiempos.push((i + 1) as f64 / n as f64);
+        }
+        let animacion = Animate::new()
+            .set("attributeName", "visibility")
+            .set("values", valores.join(";"))
+            .set("keyTimes", tiempos.iter().map(|t| format!("{:.6}", t)).collect::<Vec<String>>().join(";"))
+            .set("calcMode", "discrete")
+            .set("dur", format!("{}s", duracion * n as f64))
+            .set("repeatCount", "indefinite");
+        documento = documento.add(cuadro(&mapa, instancia, recorrido, descripcion).add(animacion));
+    }
+
+    svg::save(ruta, &documento).expect("No se pudo guardar el SVG");
+}
+
+/// Funcion encargada de generar la evolucion de un recorrido como una secuencia de cuadros.
+///
+/// Funciona igual que generar_animacion, pero cada cuadro se guarda en un svg numerado
+/// `svgs/<nombre>_<numero>.svg`, todos con la misma escala.
+///
+pub fn generar_cuadros(instancia: &Instancia, cuadros: &[(String, Vec<i64>)], proyeccion: Proyeccion, nombre: String) {
+    if cuadros.is_empty() {
+        println!("No hay cuadros para generar la animacion.");
+        return;
+    }
+
+    let mapa = Mapa::new(instancia, &cuadros[0].1, proyeccion, 1600.0, 1000.0, 60.0);
+    for (i, (descripcion, recorrido)) in cuadros.iter().enumerate() {
+        let documento = mapa.documento().add(cuadro(&mapa, instancia, recorrido, descripcion));
+        let ruta = format!("svgs/{}_{:04}.svg", nombre, i);
+        svg::save(ruta, &documento).expect("No se pudo guardar el SVG");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instancia::Metrica;
+
+    #[test]
+    fn ok_proyectar() {
+        assert_eq!(Proyeccion::Equirectangular.proyectar(10.0, 20.0), (20.0, 10.0));
+        let (x, y) = Proyeccion::Mercator.proyectar(0.0, 20.0);
+        assert_eq!(x, 20.0);
+        assert!(y.abs() < 1e-9);
+        let (_, y) = Proyeccion::Mercator.proyectar(60.0, 0.0);
+        assert!(y > 60.0);
+        assert_eq!(Proyeccion::Mercator.proyectar(90.0, 0.0), Proyeccion::Mercator.proyectar(85.0, 0.0));
+    }
+
+    #[test]
+    fn ok_mapa() {
+        let instancia = Instancia::desde_coordenadas("prueba", vec![1, 2, 3], vec![(0.0, 0.0), (10.0, 10.0), (0.0, 20.0)], Metrica::Geografica);
+        let mapa = Mapa::new(&instancia, &[1, 2, 3], Proyeccion::Equirectangular, 240.0, 140.0, 20.0);
+
+        assert_eq!(mapa.posicion(1), (20.0, 120.0));
+        assert_eq!(mapa.posicion(2), (120.0, 20.0));
+        assert_eq!(mapa.posicion(3), (220.0, 120.0));
+    }
+}
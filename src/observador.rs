@@ -0,0 +1,257 @@
+use std::sync::{Arc, Mutex};
+
+/// Estructura correspondiente al estado del recocido que se reporta a los observadores.
+///
+/// - lotes:usize : Cantidad de lotes calculados.
+/// - temperatura:f64 : Temperatura actual.
+/// - costo_actual:f64 : Peso de la solucion actual.
+/// - mejor:f64 : Peso de la mejor solucion encontrada.
+/// - tasa_aceptacion:f64 : Tasa de aceptacion del ultimo lote.
+/// - aceptadas:usize : Cantidad de soluciones aceptadas.
+///
+#[derive(Clone, Debug)]
+pub struct Progreso {
+    pub lotes: usize,
+    pub temperatura: f64,
+    pub costo_actual: f64,
+    pub mejor: f64,
+    pub tasa_aceptacion: f64,
+    pub aceptadas: usize,
+}
+
+/// Enumeracion correspondiente a la decision de un observador al terminar un lote.
+///
+/// - Continuar : El recocido continua.
+/// - Detener : El recocido termina, conservando la mejor solucion encontrada.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Control {
+    Continuar,
+    Detener,
+}
+
+/// Trait correspondiente a un observador del recocido.
+///
+/// Un observador se registra en el Tsp con `agregar_observador` y recibe los eventos del recocido.
+/// Todos los eventos tienen una implementacion vacia, por lo que cada observador implementa unicamente los que necesita.
+///
+/// # Example
+/// ```
+/// struct Contador(usize);
+/// impl Observador for Contador {
+///     fn nueva_mejor(&mut self, _aceptada: usize, _costo: f64, _recorrido: &[i64]) {
+///         self.0 += 1;
+///     }
+/// }
+/// tsp.agregar_observador(Contador(0));
+/// ```
+pub trait Observador: Send {
+
+    /// Evento que se emite por cada solucion aceptada, indicando si fue una mejora de la mejor solucion.
+    ///
+    fn solucion_aceptada(&mut self, _valor: f64, _es_mejora: bool) {}
+
+    /// Evento que se emite al encontrar una nueva mejor solucion.
+    ///
+    fn nueva_mejor(&mut self, _aceptada: usize, _costo: f64, _recorrido: &[i64]) {}
+
+    /// Evento que se emite al terminar un lote. Si algun observador regresa `Control::Detener` el recocido termina.
+    ///
+    fn lote_completado(&mut self, _progreso: &Progreso) -> Control {
+        Control::Continuar
+    }
+
+    /// Evento que se emite al disminuir la temperatura.
+    ///
+    fn temperatura_cambiada(&mut self, _anterior: f64, _nueva: f64) {}
+
+    /// Evento que se emite al terminar el recocido.
+    ///
+    fn ejecucion_terminada(&mut self, _progreso: &Progreso) {}
+}
+
+/// Un observador compartido permite consultar su informacion despues de registrarlo en el Tsp.
+impl<T: Observador> Observador for Arc<Mutex<T>> {
+    fn solucion_aceptada(&mut self, valor: f64, es_mejora: bool) {
+        self.lock().unwrap().solucion_aceptada(valor, es_mejora);
+    }
+
+    fn nueva_mejor(&mut self, aceptada: usize, costo: f64, recorrido: &[i64]) {
+        self.lock().unwrap().nueva_mejor(aceptada, costo, recorrido);
+    }
+
+    fn lote_completado(&mut self, progreso: &Progreso) -> Control {
+        self.lock().unwrap().lote_completado(progreso)
+    }
+
+    fn temperatura_cambiada(&mut self, anterior: f64, nueva: f64) {
+        self.lock().unwrap().temperatura_cambiada(anterior, nueva);
+    }
+
+    fn ejecucion_terminada(&mut self, progreso: &Progreso) {
+        self.lock().unwrap().ejecucion_terminada(progreso);
+    }
+}
+
+/// Estructura correspondiente al observador que reporta el progreso cada cierta cantidad de lotes.
+///
+/// - cada:usize : Cantidad de lotes entre cada reporte.
+/// - funcion : Funcion que recibe el progreso.
+///
+pub struct ReporteProgreso<F: FnMut(&Progreso) + Send> {
+    cada: usize,
+    funcion: F,
+}
+
+impl<F: FnMut(&Progreso) + Send> ReporteProgreso<F> {
+
+    /// Constructor del reporte de progreso.
+    ///
+    pub fn new(cada: usize, funcion: F) -> Self {
+        ReporteProgreso { cada: cada.max(1), funcion }
+    }
+}
+
+impl<F: FnMut(&Progreso) + Send> Observador for ReporteProgreso<F> {
+    fn lote_completado(&mut self, progreso: &Progreso) -> Control {
+        if progreso.lotes.is_multiple_of(self.cada) {
+            (self.funcion)(progreso);
+        }
+        Control::Continuar
+    }
+}
+
+/// Estructura correspondiente a una instantanea de la mejor solucion.
+///
+/// - aceptada:usize : Numero de solucion aceptada en la que se encontro la mejora.
+/// - costo:f64 : Peso de la mejor solucion.
+/// - recorrido:`Vec<i64>` : Recorrido de la mejor solucion.
+///
+#[derive(Clone, Debug)]
+pub struct Instantanea {
+    pub aceptada: usize,
+    pub costo: f64,
+    pub recorrido: Vec<i64>,
+}
+
+/// Estructura correspondiente al observador que registra instantaneas de la mejor solucion.
+///
+/// - instantaneas:`Vec<Instantanea>` : Instantaneas registradas.
+/// - limite:usize : Cantidad maxima de instantaneas, con 0 no se registran.
+/// - paso:usize : Cada cuantas mejoras se registra una instantanea.
+/// - mejoras:usize : Cantidad de mejoras encontradas.
+///
+pub struct Instantaneas {
+    pub instantaneas: Vec<Instantanea>,
+    limite: usize,
+    paso: usize,
+    mejoras: usize,
+}
+
+impl Instantaneas {
+
+    /// Constructor del registro de instantaneas con la cantidad maxima dada.
+    ///
+    pub fn new(limite: usize) -> Self {
+        Instantaneas { instantaneas: Vec::new(), limite, paso: 1, mejoras: 0 }
+    }
+}
+
+impl Observador for Instantaneas {
+
+    /// Para no exceder el limite de instantaneas, al alcanzarlo se conserva una de cada dos instantaneas
+    /// y a partir de ese momento se registra una de cada dos mejoras. De esta manera las instantaneas
+    /// siempre cubren la ejecucion completa.
+    ///
+    fn nueva_mejor(&mut self, aceptada: usize, costo: f64, recorrido: &[i64]) {
+        if self.limite == 0 {
+            return;
+        }
+        self.mejoras += 1;
+        if !(self.mejoras - 1).is_multiple_of(self.paso) {
+            return;
+        }
+        if self.instantaneas.len() >= self.limite {
+            let mut i = 0;
+            self.instantaneas.retain(|_| {
+                i += 1;
+                i % 2 == 1
+            });
+            self.paso *= 2;
+            if !(self.mejoras - 1).is_multiple_of(self.paso) {
+                return;
+            }
+        }
+        self.instantaneas.push(Instantanea { aceptada, costo, recorrido: recorrido.to_vec() });
+    }
+}
+
+/// Estructura correspondiente al observador que detiene el recocido al no encontrar mejoras.
+///
+/// - paciencia:usize : Cantidad de lotes consecutivos sin mejorar la mejor solucion antes de detener el recocido.
+/// - sin_mejora:usize : Cantidad de lotes consecutivos sin mejora.
+/// - mejor:f64 : Mejor solucion al terminar el ultimo lote.
+///
+pub struct DetencionTemprana {
+    paciencia: usize,
+    sin_mejora: usize,
+    mejor: f64,
+}
+
+impl DetencionTemprana {
+
+    /// Constructor de la detencion temprana con la cantidad de lotes sin mejora dada.
+    ///
+    pub fn new(paciencia: usize) -> Self {
+        DetencionTemprana { paciencia: paciencia.max(1), sin_mejora: 0, mejor: f64::MAX }
+    }
+}
+
+impl Observador for DetencionTemprana {
+    fn lote_completado(&mut self, progreso: &Progreso) -> Control {
+        if progreso.mejor < self.mejor {
+            self.mejor = progreso.mejor;
+            self.sin_mejora = 0;
+        } else {
+            self.sin_mejora += 1;
+        }
+        if self.sin_mejora >= self.paciencia { Control::Detener } else { Control::Continuar }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progreso(lotes: usize, mejor: f64) -> Progreso {
+        Progreso { lotes, temperatura: 1.0, costo_actual: mejor, mejor, tasa_aceptacion: 0.5, aceptadas: 0 }
+    }
+
+    #[test]
+    fn ok_instantaneas() {
+        let mut instantaneas = Instantaneas::new(4);
+        for i in 0..10 {
+            instantaneas.nueva_mejor(i, 10.0 - i as f64, &[1, 2, 3]);
+        }
+        let costos: Vec<f64> = instantaneas.instantaneas.iter().map(|s| s.costo).collect();
+        assert_eq!(costos, vec![10.0, 6.0, 2.0]);
+    }
+
+    #[test]
+    fn ok_detencion_temprana() {
+        let mut detencion = DetencionTemprana::new(2);
+        assert_eq!(detencion.lote_completado(&progreso(1, 5.0)), Control::Continuar);
+        assert_eq!(detencion.lote_completado(&progreso(2, 5.0)), Control::Continuar);
+        assert_eq!(detencion.lote_completado(&progreso(3, 4.0)), Control::Continuar);
+        assert_eq!(detencion.lote_completado(&progreso(4, 4.0)), Control::Continuar);
+        assert_eq!(detencion.lote_completado(&progreso(5, 4.0)), Control::Detener);
+    }
+
+    #[test]
+    fn ok_observador_compartido() {
+        let compartido = Arc::new(Mutex::new(Instantaneas::new(10)));
+        let mut observador: Box<dyn Observador> = Box::new(Arc::clone(&compartido));
+        observador.nueva_mejor(3, 1.5, &[2, 1]);
+        assert_eq!(compartido.lock().unwrap().instantaneas.len(), 1);
+    }
+}
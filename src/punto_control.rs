@@ -0,0 +1,75 @@
+use crate::tsp::{Lote, Parametros};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::BufReader;
+
+/// Estructura correspondiente al estado completo de un recocido en un punto de control.
+///
+/// Se toma al terminar un lote, de tal manera que al reanudar el recocido desde el punto de control
+/// se obtiene exactamente el mismo resultado que en una ejecucion sin interrupciones con la misma semilla.
+///
+/// - semilla:i64 : Semilla con la que se inicializo el generador de numeros pseudoaleatorios.
+/// - posicion_rng:u128 : Cantidad de palabras consumidas del generador de numeros pseudoaleatorios.
+/// - parametros:Parametros : Parametros del recocido.
+/// - solucion_actual:`Vec<i64>` : Solucion actual.
+/// - peso_solucion_actual:f64 : Peso de la solucion actual.
+/// - mejor_solucion_arr:`Vec<i64>` : Mejor solucion encontrada.
+/// - mejor_solucion:f64 : Peso de la mejor solucion encontrada.
+/// - temperatura:f64 : Temperatura actual.
+/// - temperatura_inicial:f64 : Temperatura inicial calculada.
+/// - promedio:f64 : Promedio del ultimo lote.
+/// - umbral:f64 : Promedio del lote anterior, con el que se decide si se calcula otro lote con la misma temperatura.
+/// - aceptadas:usize : Cantidad de soluciones aceptadas.
+/// - lotes:usize : Cantidad de lotes calculados.
+/// - historial_lotes:`Vec<Lote>` : Informacion de cada lote calculado.
+/// - detenido:bool : Indica si algun observador solicito detener el recocido.
+///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PuntoControl {
+    pub semilla: i64,
+    pub posicion_rng: u128,
+    pub parametros: Parametros,
+    pub solucion_actual: Vec<i64>,
+    pub peso_solucion_actual: f64,
+    pub mejor_solucion_arr: Vec<i64>,
+    pub mejor_solucion: f64,
+    pub temperatura: f64,
+    pub temperatura_inicial: f64,
+    pub promedio: f64,
+    pub umbral: f64,
+    pub aceptadas: usize,
+    pub lotes: usize,
+    pub historial_lotes: Vec<Lote>,
+    pub detenido: bool,
+}
+
+impl PuntoControl {
+
+    /// Funcion encargada de escribir el punto de control en un archivo json.
+    ///
+    /// Se escribe primero en un archivo temporal que despues se renombra, de esta forma si el proceso
+    /// termina mientras se escribe se conserva el punto de control anterior.
+    ///
+    /// # Example
+    /// ```
+    /// punto.escribir_json("resultados/tsp_semilla_1_punto_control.json")?;
+    /// ```
+    pub fn escribir_json(&self, ruta: &str) -> std::io::Result<()> {
+        let temporal = format!("{}.tmp", ruta);
+        let file = File::create(&temporal)?;
+        serde_json::to_writer(&file, self)?;
+        file.sync_all()?;
+        fs::rename(&temporal, ruta)
+    }
+
+    /// Funcion encargada de leer un punto de control de un archivo json.
+    ///
+    /// # Example
+    /// ```
+    /// let punto = PuntoControl::leer_json("resultados/tsp_semilla_1_punto_control.json")?;
+    /// ```
+    pub fn leer_json(ruta: &str) -> std::io::Result<Self> {
+        let file = File::open(ruta)?;
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+}
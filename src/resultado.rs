@@ -0,0 +1,267 @@
+use crate::analisis::Evaluacion;
+use crate::instancia::Instancia;
+use crate::tsp::{barrido_recorrido, Parametros, Tsp};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::time::Instant;
+
+/// Enumeracion correspondiente al estado en el que termino una ejecucion.
+///
+/// - Completada : El recocido alcanzo la temperatura minima.
+/// - Detenida : El recocido se detuvo antes por no encontrar mejoras.
+/// - Interrumpida : La ejecucion se cancelo, el resultado corresponde a la mejor solucion encontrada hasta ese momento y no se aplico el barrido.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Estado {
+    #[default]
+    Completada,
+    Detenida,
+    Interrumpida,
+}
+
+/// Estructura correspondiente al resultado de una ejecucion del tsp.
+///
+/// Almacena la informacion necesaria para identificar y comparar una ejecucion:
+/// - instancia:String : Nombre del archivo de ciudades.
+/// - hash_instancia:String : Hash de la lista de ciudades, permite identificar la instancia aunque cambie el nombre.
+/// - hash_parametros:String : Hash de los parametros del recocido.
+/// - semilla:i64 : Semilla con la que se ejecuto el tsp.
+/// - parametros:Parametros : Parametros del recocido.
+/// - temperatura_inicial:f64 : Temperatura calculada al iniciar el recocido.
+/// - temperatura_final:f64 : Temperatura al terminar el recocido.
+/// - costo_antes_barrido:f64 : Peso de la mejor solucion del recocido.
+/// - costo:f64 : Peso de la mejor solucion despues del barrido.
+/// - solucion:`Vec<i64>` : Recorrido de la mejor solucion.
+/// - lotes:usize : Cantidad de lotes calculados.
+/// - aceptadas:usize : Cantidad de soluciones aceptadas.
+/// - tiempo_recocido:f64 : Tiempo en segundos del recocido.
+/// - tiempo_barrido:f64 : Tiempo en segundos del barrido.
+/// - tiempo:f64 : Tiempo total en segundos de la ejecucion.
+/// - curva_mejor:`Vec<f64>` : Curva muestreada de la mejor solucion encontrada durante el recocido.
+/// - estado:Estado : Estado en el que termino la ejecucion.
+/// - aristas_penalizadas:usize : Cantidad de aristas de la solucion que no existen en la base de datos.
+/// - factible:bool : Indica si la solucion no tiene aristas penalizadas.
+/// - normalizador:f64 : Normalizador de la instancia, al multiplicarlo por el costo se obtiene la longitud del recorrido.
+/// - longitud:f64 : Longitud de la solucion sin normalizar, en metros para las instancias geograficas.
+/// - factor_penalizacion:f64 : Distancia maxima de la instancia, por la que se multiplica la distancia natural de las aristas penalizadas.
+///
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Resultado {
+    pub instancia: String,
+    pub hash_instancia: String,
+    pub hash_parametros: String,
+    pub semilla: i64,
+    pub parametros: Parametros,
+    pub temperatura_inicial: f64,
+    pub temperatura_final: f64,
+    pub costo_antes_barrido: f64,
+    pub costo: f64,
+    pub solucion: Vec<i64>,
+    pub lotes: usize,
+    pub aceptadas: usize,
+    pub tiempo_recocido: f64,
+    pub tiempo_barrido: f64,
+    pub tiempo: f64,
+    pub curva_mejor: Vec<f64>,
+    pub estado: Estado,
+    pub aristas_penalizadas: usize,
+    pub factible: bool,
+    pub normalizador: f64,
+    pub longitud: f64,
+    pub factor_penalizacion: f64,
+}
+
+impl Resultado {
+
+    /// Funcion encargada de obtener el resultado de un recocido ya ejecutado.
+    ///
+    /// Los parametros estan dados por:
+    /// - tsp:&Tsp : Tsp sobre el que ya se ejecuto el recocido, el nombre y el hash se toman de su instancia.
+    /// - tiempo_recocido:f64 : Tiempo en segundos del recocido.
+    ///
+    /// Aplica el barrido a la mejor solucion del recocido, salvo que el recocido haya sido interrumpido, en cuyo caso
+    /// el resultado corresponde a la mejor solucion encontrada hasta ese momento. La curva de la mejor solucion queda vacia.
+    ///
+    /// # Example
+    /// ```
+    /// tsp.aceptacion_por_umbrales();
+    /// let resultado = Resultado::desde_tsp(&tsp, inicio.elapsed().as_secs_f64());
+    /// ```
+    pub fn desde_tsp(tsp: &Tsp, tiempo_recocido: f64) -> Self {
+        let estado = if tsp.interrumpido() {
+            Estado::Interrumpida
+        } else if tsp.detenido() {
+            Estado::Detenida
+        } else {
+            Estado::Completada
+        };
+        let (costo_recocido, solucion_recocido) = if tsp.mejor_solucion_arr.is_empty() {
+            (tsp.peso_solucion_actual, tsp.solucion_actual.clone())
+        } else {
+            (tsp.mejor_solucion, tsp.mejor_solucion_arr.clone())
+        };
+
+        let inicio_barrido = Instant::now();
+        let mut solucion = solucion_recocido;
+        let costo = if estado == Estado::Interrumpida {
+            costo_recocido
+        } else {
+            barrido_recorrido(tsp.get_instancia(), &mut solucion)
+        };
+        let tiempo_barrido = inicio_barrido.elapsed().as_secs_f64();
+        let evaluacion = Evaluacion::new(tsp.get_instancia(), &solucion);
+
+        Resultado {
+            instancia: tsp.get_instancia().nombre.clone(),
+            hash_instancia: tsp.get_instancia().hash.clone(),
+            hash_parametros: hash_parametros(&tsp.parametros),
+            semilla: tsp.get_semilla(),
+            parametros: tsp.parametros.clone(),
+            temperatura_inicial: tsp.temperatura_inicial,
+            temperatura_final: tsp.get_temperatura(),
+            costo_antes_barrido: costo_recocido,
+            costo,
+            solucion,
+            lotes: tsp.lotes,
+            aceptadas: tsp.aceptadas,
+            tiempo_recocido,
+            tiempo_barrido,
+            tiempo: tiempo_recocido + tiempo_barrido,
+            curva_mejor: Vec::new(),
+            estado,
+            aristas_penalizadas: evaluacion.penalizadas,
+            factible: evaluacion.factible(),
+            normalizador: evaluacion.normalizador,
+            longitud: evaluacion.longitud,
+            factor_penalizacion: evaluacion.distancia_maxima,
+        }
+    }
+
+    /// Funcion encargada de recalcular los campos que se derivan de la solucion y de la instancia.
+    ///
+    /// Los json escritos antes de agregar las aristas penalizadas, la factibilidad, el normalizador, la longitud y el factor de penalizacion
+    /// se leen con esos campos en cero, por lo que al reutilizar un resultado se recalculan a partir de la solucion.
+    ///
+    /// # Example
+    /// ```
+    /// let mut resultado = Resultado::leer_json("resultados/tsp_semilla_1.json")?;
+    /// resultado.completar(&instancia);
+    /// ```
+    pub fn completar(&mut self, instancia: &Instancia) {
+        let evaluacion = Evaluacion::new(instancia, &self.solucion);
+        self.aristas_penalizadas = evaluacion.penalizadas;
+        self.factible = evaluacion.factible();
+        self.normalizador = evaluacion.normalizador;
+        self.longitud = evaluacion.longitud;
+        self.factor_penalizacion = evaluacion.distancia_maxima;
+    }
+
+    /// Funcion encargada de escribir el resultado en un archivo json.
+    ///
+    /// # Example
+    /// ```
+    /// resultado.escribir_json("resultados/tsp_semilla_1.json")?;
+    /// ```
+    pub fn escribir_json(&self, ruta: &str) -> std::io::Result<()> {
+        let file = File::create(ruta)?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    /// Funcion encargada de leer un resultado de un archivo json.
+    ///
+    /// # Example
+    /// ```
+    /// let resultado = Resultado::leer_json("resultados/tsp_semilla_1.json")?;
+    /// ```
+    pub fn leer_json(ruta: &str) -> std::io::Result<Self> {
+        let file = File::open(ruta)?;
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+}
+
+/// Funcion encargada de calcular el hash FNV-1a de una secuencia de bytes.
+///
+/// A diferencia del hasher de la biblioteca estandar, el valor no cambia entre ejecuciones
+/// ni entre versiones del compilador, por lo que puede guardarse en archivos.
+///
+pub(crate) fn fnv(bytes: impl Iterator<Item = u8>) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Funcion encargada de calcular el hash de una instancia.
+///
+/// Calcula el hash sobre los indices de las ciudades en el orden dado. Regresa el hash en hexadecimal.
+///
+/// # Example
+/// ```
+/// let hash = hash_instancia(&ciudades);
+/// ```
+pub fn hash_instancia(ciudades: &[i64]) -> String {
+    fnv(ciudades.iter().flat_map(|c| c.to_le_bytes()))
+}
+
+/// Funcion encargada de calcular el hash de los parametros del recocido.
+///
+/// Regresa el hash en hexadecimal.
+///
+pub fn hash_parametros(parametros: &Parametros) -> String {
+    let json = serde_json::to_string(parametros).expect("No se pudieron serializar los parametros");
+    fnv(json.bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_hash_instancia() {
+        assert_eq!(hash_instancia(&[]), "cbf29ce484222325");
+        assert_eq!(hash_instancia(&[1, 2, 3]), hash_instancia(&[1, 2, 3]));
+        assert_ne!(hash_instancia(&[1, 2, 3]), hash_instancia(&[3, 2, 1]));
+
+        let otros = Parametros { phi: 0.9, ..Parametros::default() };
+        assert_eq!(hash_parametros(&Parametros::default()), hash_parametros(&Parametros::default()));
+        assert_ne!(hash_parametros(&Parametros::default()), hash_parametros(&otros));
+    }
+
+    #[test]
+    fn ok_json() {
+        let resultado = Resultado { semilla: 4, costo: 0.5, solucion: vec![1, 2], ..Resultado::default() };
+        let json = serde_json::to_string(&resultado).unwrap();
+        let leido: Resultado = serde_json::from_str(&json).unwrap();
+        assert_eq!(leido.semilla, 4);
+        assert_eq!(leido.costo, 0.5);
+        assert_eq!(leido.solucion, vec![1, 2]);
+        assert_eq!(leido.parametros, Parametros::default());
+        assert_eq!(leido.estado, Estado::Completada);
+
+        let interrumpido = Resultado { estado: Estado::Interrumpida, ..Resultado::default() };
+        let json = serde_json::to_string(&interrumpido).unwrap();
+        assert!(json.contains("\"estado\":\"interrumpida\""));
+        assert_eq!(serde_json::from_str::<Resultado>(&json).unwrap().estado, Estado::Interrumpida);
+    }
+
+    #[test]
+    fn ok_completar() {
+        let distancias = vec![0.0, 1.0, 4.0, 1.0, 0.0, 2.0, 4.0, 2.0, 0.0];
+        let aristas = vec![false, true, false, true, false, true, false, true, false];
+        let instancia = Instancia::new("prueba", vec![1, 2, 3], vec![(0.0, 0.0); 3], distancias, aristas);
+        let mut anterior: Resultado = serde_json::from_str("{\"semilla\":1,\"costo\":0.5,\"solucion\":[1,3,2]}").unwrap();
+        assert_eq!(anterior.longitud, 0.0);
+        anterior.completar(&instancia);
+        assert_eq!(anterior.longitud, 6.0);
+        assert_eq!(anterior.aristas_penalizadas, 1);
+        assert!(!anterior.factible);
+        assert_eq!(anterior.normalizador, instancia.normalizador);
+        assert_eq!(anterior.factor_penalizacion, instancia.distancia_maxima);
+    }
+}
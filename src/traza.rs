@@ -0,0 +1,255 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+
+/// Cantidad maxima de puntos que se conservan por omision en la traza en memoria.
+pub const LIMITE_TRAZA: usize = 1 << 20;
+
+/// Encabezado de los archivos binarios de traza.
+const MAGICO: &[u8; 4] = b"TRZ1";
+
+/// Estructura correspondiente a una serie de soluciones aceptadas con memoria acotada.
+///
+/// Cada punto resume un bloque de `paso` soluciones aceptadas consecutivas: el peso promedio del bloque
+/// y, por separado, si el bloque contiene una mejora de la mejor solucion. Al alcanzar el limite de puntos se combinan
+/// los puntos de dos en dos promediando sus pesos y se duplica el paso, de esta manera la serie siempre cubre la ejecucion
+/// completa y conserva la forma de la curva de soluciones aceptadas en lugar de su envolvente inferior.
+///
+/// - puntos:`Vec<(f64, bool)>` : Puntos de la serie.
+/// - paso:usize : Cantidad de soluciones aceptadas que resume cada punto.
+/// - limite:usize : Cantidad maxima de puntos.
+/// - bloque:`Option<(f64, bool)>` : Suma de los pesos y mejora del bloque en construccion.
+/// - en_bloque:usize : Cantidad de soluciones aceptadas del bloque en construccion.
+///
+#[derive(Clone, Debug)]
+pub struct Decimador {
+    pub puntos: Vec<(f64, bool)>,
+    pub paso: usize,
+    limite: usize,
+    bloque: Option<(f64, bool)>,
+    en_bloque: usize,
+}
+
+impl Decimador {
+
+    /// Constructor del decimador con la cantidad maxima de puntos dada.
+    ///
+    pub fn new(limite: usize) -> Self {
+        Decimador { puntos: Vec::new(), paso: 1, limite: limite.max(2), bloque: None, en_bloque: 0 }
+    }
+
+    /// Funcion encargada de agregar una solucion aceptada a la serie.
+    ///
+    pub fn agregar(&mut self, valor: f64, es_mejora: bool) {
+        self.bloque = Some(match self.bloque {
+            Some((suma, mejora)) => (suma + valor, mejora || es_mejora),
+            None => (valor, es_mejora),
+        });
+        self.en_bloque += 1;
+        if self.en_bloque < self.paso {
+            return;
+        }
+        if let Some((suma, mejora)) = self.bloque.take() {
+            self.puntos.push((suma / self.en_bloque as f64, mejora));
+        }
+        self.en_bloque = 0;
+        if self.puntos.len() >= self.limite {
+            self.puntos = self
+                .puntos
+                .chunks(2)
+                .map(|par| (par.iter().map(|(v, _)| v).sum::<f64>() / par.len() as f64, par.iter().any(|(_, m)| *m)))
+                .collect();
+            self.paso *= 2;
+        }
+    }
+
+    /// Funcion encargada de obtener la serie, incluyendo el bloque incompleto.
+    ///
+    /// Regresa los puntos y el paso de la serie.
+    ///
+    pub fn serie(&self) -> (Vec<(f64, bool)>, usize) {
+        let mut puntos = self.puntos.clone();
+        puntos.extend(self.bloque.map(|(suma, mejora)| (suma / self.en_bloque as f64, mejora)));
+        (puntos, self.paso)
+    }
+}
+
+/// Enumeracion correspondiente al destino de la traza de soluciones aceptadas.
+///
+/// - Apagada : No se registra la traza.
+/// - Memoria : Se conserva en memoria una serie decimada con una cantidad maxima de puntos.
+/// - Archivo : Cada solucion aceptada se escribe en un archivo conforme se acepta. Si la ruta termina en `.bin`
+///   se escribe en formato binario (peso en f64 little endian y un byte con la mejora), en otro caso en csv.
+///
+pub enum Traza {
+    Apagada,
+    Memoria(Decimador),
+    Archivo {
+        ruta: String,
+        escritor: BufWriter<File>,
+        binario: bool,
+        error: Option<io::Error>,
+    },
+}
+
+impl Default for Traza {
+    fn default() -> Self {
+        Traza::Memoria(Decimador::new(LIMITE_TRAZA))
+    }
+}
+
+impl Traza {
+
+    /// Constructor de la traza que se escribe en un archivo.
+    ///
+    /// # Example
+    /// ```
+    /// tsp.establecer_traza(Traza::archivo("resultados/tsp_semilla_1_traza.bin")?);
+    /// ```
+    pub fn archivo(ruta: &str) -> io::Result<Self> {
+        let binario = ruta.ends_with(".bin");
+        let mut escritor = BufWriter::new(File::create(ruta)?);
+        if binario {
+            escritor.write_all(MAGICO)?;
+        } else {
+            writeln!(escritor, "valor,es_mejora")?;
+        }
+        Ok(Traza::Archivo { ruta: ruta.to_string(), escritor, binario, error: None })
+    }
+
+    /// Funcion encargada de registrar una solucion aceptada.
+    ///
+    /// En caso de un error de escritura se deja de escribir y el error se regresa al cerrar la traza.
+    ///
+    pub fn registrar(&mut self, valor: f64, es_mejora: bool) {
+        match self {
+            Traza::Apagada => {}
+            Traza::Memoria(decimador) => decimador.agregar(valor, es_mejora),
+            Traza::Archivo { escritor, binario, error, .. } => {
+                if error.is_some() {
+                    return;
+                }
+                let resultado = if *binario {
+                    escritor.write_all(&valor.to_le_bytes()).and_then(|_| escritor.write_all(&[es_mejora as u8]))
+                } else {
+                    writeln!(escritor, "{},{}", valor, es_mejora as u8)
+                };
+                if let Err(e) = resultado {
+                    *error = Some(e);
+                }
+            }
+        }
+    }
+
+    /// Funcion encargada de terminar de escribir la traza.
+    ///
+    /// Regresa el primer error de escritura, en caso de haberlo.
+    ///
+    pub fn cerrar(&mut self) -> io::Result<()> {
+        if let Traza::Archivo { escritor, error, .. } = self {
+            if let Some(e) = error.take() {
+                return Err(e);
+            }
+            escritor.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Funcion encargada de obtener una muestra de la traza con a lo mas la cantidad de puntos dada.
+    ///
+    /// La traza en archivo se lee de regreso decimandola conforme se lee, por lo que no se carga completa en memoria.
+    /// Regresa los puntos y la cantidad de soluciones aceptadas que resume cada punto.
+    ///
+    /// # Example
+    /// ```
+    /// let (puntos, paso) = tsp.tomar_traza().muestra(100_000)?;
+    /// ```
+    pub fn muestra(&mut self, max_puntos: usize) -> io::Result<(Vec<(f64, bool)>, usize)> {
+        match self {
+            Traza::Apagada => Ok((Vec::new(), 1)),
+            Traza::Memoria(decimador) => {
+                let (puntos, paso) = decimador.serie();
+                let mut reducido = Decimador::new(max_puntos);
+                for (valor, es_mejora) in puntos {
+                    reducido.agregar(valor, es_mejora);
+                }
+                let (puntos, factor) = reducido.serie();
+                Ok((puntos, paso * factor))
+            }
+            Traza::Archivo { .. } => {
+                self.cerrar()?;
+                let Traza::Archivo { ruta, .. } = self else { unreachable!() };
+                leer_traza(ruta, max_puntos)
+            }
+        }
+    }
+}
+
+/// Funcion encargada de leer una traza escrita en un archivo csv o binario.
+///
+/// La traza se decima conforme se lee para no exceder la cantidad de puntos dada.
+/// Regresa los puntos y la cantidad de soluciones aceptadas que resume cada punto.
+///
+/// # Example
+/// ```
+/// let (puntos, paso) = leer_traza("resultados/tsp_semilla_1_traza.csv", 100_000)?;
+/// ```
+pub fn leer_traza(ruta: &str, max_puntos: usize) -> io::Result<(Vec<(f64, bool)>, usize)> {
+    let mut lector = BufReader::new(File::open(ruta)?);
+    let mut decimador = Decimador::new(max_puntos);
+    if ruta.ends_with(".bin") {
+        let mut magico = [0u8; 4];
+        lector.read_exact(&mut magico)?;
+        if &magico != MAGICO {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{} no es una traza binaria", ruta)));
+        }
+        let mut registro = [0u8; 9];
+        loop {
+            match lector.read_exact(&mut registro) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            }
+            let valor = f64::from_le_bytes(registro[..8].try_into().unwrap());
+            decimador.agregar(valor, registro[8] != 0);
+        }
+    } else {
+        for linea in lector.lines().skip(1) {
+            let linea = linea?;
+            let Some((valor, es_mejora)) = linea.split_once(',') else { continue };
+            let valor = valor.trim().parse::<f64>().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            decimador.agregar(valor, es_mejora.trim() == "1");
+        }
+    }
+    Ok(decimador.serie())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_decimador() {
+        let mut decimador = Decimador::new(4);
+        for i in 0..10 {
+            decimador.agregar(10.0 - i as f64, i == 7);
+        }
+        let (puntos, paso) = decimador.serie();
+        assert_eq!(paso, 4);
+        assert_eq!(puntos, vec![(8.5, false), (4.5, true), (1.5, false)]);
+    }
+
+    #[test]
+    fn ok_archivo() {
+        for extension in ["csv", "bin"] {
+            let ruta = std::env::temp_dir().join(format!("traza_prueba_{}.{}", std::process::id(), extension));
+            let ruta = ruta.to_str().unwrap();
+            let mut traza = Traza::archivo(ruta).unwrap();
+            traza.registrar(3.5, true);
+            traza.registrar(4.0, false);
+            traza.registrar(2.25, true);
+            assert_eq!(traza.muestra(10).unwrap(), (vec![(3.5, true), (4.0, false), (2.25, true)], 1));
+            assert_eq!(leer_traza(ruta, 2).unwrap(), (vec![(3.75, true), (2.25, true)], 2));
+            std::fs::remove_file(ruta).unwrap();
+        }
+    }
+}
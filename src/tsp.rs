@@ -1,80 +1,205 @@
 use rand::SeedableRng;
 use rand::Rng;
-use rand::rngs::StdRng;
-use crate::grafica::Grafica;
+use rand_chacha::ChaCha12Rng;
+use crate::instancia::Instancia;
+use crate::observador::{Control, Observador, Progreso, ReporteProgreso};
+use crate::cancelacion::Cancelacion;
+use crate::punto_control::PuntoControl;
+use crate::traza::Traza;
+use serde::{Deserialize, Serialize};
+
+/// Estructura correspondiente a los parametros del recocido.
+///
+/// - temperatura:f64 : Temperatura a partir de la cual se busca la temperatura inicial.
+/// - epsilon:f64 : Temperatura minima, al alcanzarla termina el algoritmo.
+/// - phi:f64 : Factor de enfriamiento de la temperatura.
+/// - tamano_lote:i64 : Cantidad de soluciones aceptadas que conforman un lote.
+/// - porcentaje:f64 : Porcentaje de aceptacion buscado al calcular la temperatura inicial.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Parametros {
+    pub temperatura: f64,
+    pub epsilon: f64,
+    pub phi: f64,
+    pub tamano_lote: i64,
+    pub porcentaje: f64,
+}
+
+impl Default for Parametros {
+    fn default() -> Self {
+        Parametros {
+            temperatura: 20000.0,
+            epsilon: 0.0001,
+            phi: 0.95,
+            tamano_lote: 7000,
+            porcentaje: 0.60,
+        }
+    }
+}
+
+/// Estructura correspondiente a la informacion de un lote calculado.
+///
+/// - aceptadas:usize : Cantidad total de soluciones aceptadas al terminar el lote.
+/// - temperatura:f64 : Temperatura con la que se calculo el lote.
+/// - tasa_aceptacion:f64 : Proporcion de vecinos aceptados respecto a los vecinos generados en el lote.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Lote {
+    pub aceptadas: usize,
+    pub temperatura: f64,
+    pub tasa_aceptacion: f64,
+}
+
+/// Funcion a la que se entregan los puntos de control del recocido.
+type FuncionPuntoControl = Box<dyn FnMut(&PuntoControl) + Send>;
 
 /// Estructura correspondiente al TSP
 ///
 /// La estructura almacena los valores que consideramos de utilidad para el TSP, entre ellos tenemos:
-/// - grafica:Grafica: Es la estructura Grafica que nos servirá para realizar el calculo de las operaciones.
-/// - soluciones_aceptadas:`Vec<f64>`: Es el vector que almacena los valores de las soluciones aceptadas.
+/// - instancia:Instancia: Es la instancia del problema, con la que se calculan los pesos entre las ciudades.
+/// - parametros:Parametros: Son los parametros con los que se ejecuta el recocido.
+/// - aceptadas:usize: Es la cantidad de soluciones aceptadas durante el recocido.
 /// - solucion_actual:`Vec<i64>`: Es el vector que almacena la solucion actual, es decir almacena las ciudades y el orden del TSP.
 /// - temperatura:f64: Es la temperatura actual del sistema, valor guardado para moverlo entre las distintas funciones.
 /// - promedio:f64: Es el promedio de la solución actual, se almacena para no tener que recalcularlo y moverlo entre funciones
+/// - umbral:f64: Es el promedio del lote anterior, mientras el promedio no lo supere se calcula otro lote con la misma temperatura.
 /// - mejor_solucion:f64: Es el peso de la mejor solucion
-/// - normalizador: Es el normalizador que nos permite calcular el peso de las soluciones.
-/// - random: Es el random que nos permite obtener distintos enteros.
+/// - random: Es el random que nos permite obtener distintos enteros, es el mismo generador que `StdRng` pero permite consultar y restaurar su posicion.
+/// - semilla:i64: Es la semilla con la que se inicializo el random.
 /// - peso_solucion_actual:f64: Es el peso de la solucion actual
 /// - mejor_solucion_arr:`Vec<i64>`: Es el vector que almacena la mejor solucion, es decir almacena las ciudades y el orden del TSP.
+/// - temperatura_inicial:f64: Es la temperatura con la que inicio el recocido, despues de ser calculada.
+/// - lotes:usize: Es la cantidad de lotes calculados durante el recocido.
+/// - historial_lotes:`Vec<Lote>`: Es la informacion de cada lote calculado durante el recocido.
+/// - observadores: Son los observadores que reciben los eventos del recocido, como el progreso o las instantaneas.
+/// - traza:Traza: Es la traza de soluciones aceptadas, el tsp es su unico dueño durante el recocido y se recupera con `tomar_traza`.
+/// - detenido:bool: Indica si algun observador solicito detener el recocido.
+/// - cancelacion:Cancelacion: Es el token que se revisa durante el recocido para terminarlo en cuanto se cancele.
+/// - puntos_control: Es la cantidad de lotes entre cada punto de control y la funcion que los recibe, en caso de solicitarse.
 ///
-pub struct Tsp {
-    grafica: Grafica,
-    pub soluciones_aceptadas: Vec<f64>,
+pub struct Tsp<'a> {
+    instancia: &'a Instancia,
+    pub parametros: Parametros,
+    pub aceptadas: usize,
     pub solucion_actual: Vec<i64>,
     temperatura: f64,
     promedio: f64,
+    umbral: f64,
     pub mejor_solucion: f64,
-    normalizador: f64,
-    random: StdRng,
+    random: ChaCha12Rng,
+    semilla: i64,
     pub peso_solucion_actual: f64,
     pub mejor_solucion_arr: Vec<i64>,
-    pub soluciones_para_svg: Vec<(f64, bool)>,
+    pub temperatura_inicial: f64,
+    pub lotes: usize,
+    pub historial_lotes: Vec<Lote>,
+    observadores: Vec<Box<dyn Observador>>,
+    traza: Traza,
+    detenido: bool,
+    cancelacion: Cancelacion,
+    puntos_control: Option<(usize, FuncionPuntoControl)>,
 }
 
-impl Tsp {
+impl<'a> Tsp<'a> {
 
     /// Constructor de la estructura TSP
     ///
     /// Recibe como parametros lo siguiente:
     /// - temperatura:f64 : Es la temperatura inicial del sistema
-    /// - solucion_actual: `Vec<i64>` : Es la solucion de ciudades que tenemos actualmente.
+    /// - instancia:&Instancia : Es la instancia del problema, la solucion actual inicia con sus ciudades en el orden en el que se dieron.
+    ///   El tsp unicamente toma prestada la instancia, por lo que varias ejecuciones pueden compartirla sin copiar sus matrices.
     /// - semilla:i64 : Es la semilla con la cual se va a inicializar nuestro generador de numeros pseudoaleatorios.
     ///
-    /// Se encarga de inicializar la estructura, en el que la mayor parte de los valores de la estructura son inicializados como valores nuevos, excepto el rng y el normalizador.
+    /// Se encarga de inicializar la estructura, en el que la mayor parte de los valores de la estructura son inicializados como valores nuevos, excepto el rng.
+    /// El normalizador es el de la instancia, que se calcula una sola vez al construirla.
     ///
-    pub fn new(temperatura: f64, grafica: Grafica, solucion_actual: Vec<i64>, semilla: i64) -> Self {
-        let rng = StdRng::seed_from_u64(semilla as u64);
-        let normalizador = Self::get_normalizador(&grafica.db.distancias_tsp, solucion_actual.len());
+    pub fn new(temperatura: f64, instancia: &'a Instancia, semilla: i64) -> Self {
+        Self::con_parametros(Parametros { temperatura, ..Parametros::default() }, instancia, semilla)
+    }
+
+    /// Constructor de la estructura TSP con parametros.
+    ///
+    /// Funciona igual que el constructor, pero recibe todos los parametros del recocido en lugar de unicamente la temperatura.
+    ///
+    /// # Example
+    /// ```
+    /// let tsp = Tsp::con_parametros(Parametros::default(), &instancia, 7);
+    /// ```
+    pub fn con_parametros(parametros: Parametros, instancia: &'a Instancia, semilla: i64) -> Self {
+        let rng = ChaCha12Rng::seed_from_u64(semilla as u64);
         Tsp {
-            grafica,
-            solucion_actual,
-            soluciones_aceptadas: Vec::new(),
-            temperatura,
+            solucion_actual: instancia.ciudades.clone(),
+            instancia,
+            aceptadas: 0,
+            temperatura: parametros.temperatura,
+            parametros,
             promedio: 0.0,
+            umbral: f64::MAX,
             mejor_solucion: f64::MAX,
-            normalizador,
             random: rng,
+            semilla,
             peso_solucion_actual: 0.0,
             mejor_solucion_arr: Vec::new(),
-            soluciones_para_svg: Vec::new(),
+            temperatura_inicial: 0.0,
+            lotes: 0,
+            historial_lotes: Vec::new(),
+            observadores: Vec::new(),
+            traza: Traza::Apagada,
+            detenido: false,
+            cancelacion: Cancelacion::new(),
+            puntos_control: None,
         }
 
         
     }
 
+    /// Constructor de la estructura TSP a partir de un punto de control.
+    ///
+    /// Restaura el estado del recocido, incluyendo la posicion del random, para continuarlo con `reanudar_recocido`.
+    /// Los observadores y el token de cancelacion no forman parte del punto de control, por lo que deben registrarse de nuevo.
+    ///
+    /// # Example
+    /// ```
+    /// let mut tsp = Tsp::desde_punto_control(punto, &instancia);
+    /// tsp.reanudar_recocido();
+    /// ```
+    pub fn desde_punto_control(punto: PuntoControl, instancia: &'a Instancia) -> Self {
+        let mut tsp = Self::con_parametros(punto.parametros, instancia, punto.semilla);
+        tsp.solucion_actual = punto.solucion_actual;
+        tsp.random.set_word_pos(punto.posicion_rng);
+        tsp.peso_solucion_actual = punto.peso_solucion_actual;
+        tsp.mejor_solucion_arr = punto.mejor_solucion_arr;
+        tsp.mejor_solucion = punto.mejor_solucion;
+        tsp.temperatura = punto.temperatura;
+        tsp.temperatura_inicial = punto.temperatura_inicial;
+        tsp.promedio = punto.promedio;
+        tsp.umbral = punto.umbral;
+        tsp.aceptadas = punto.aceptadas;
+        tsp.lotes = punto.lotes;
+        tsp.historial_lotes = punto.historial_lotes;
+        tsp.detenido = punto.detenido;
+        tsp
+    }
+
     /// Funcion encargada de calcular un lote de soluciones
     ///
     /// La funcion se encarga de calcular soluciones, sobre las cuales vamos a aceptar las
-    /// mejores soluciones hasta completar la cantidad correspondiente a un lote. Dicho esto se almacenara
-    /// las soluciones aceptadas y se calculara el promedio de las soluciones.
+    /// mejores soluciones hasta completar la cantidad correspondiente a un lote. Dicho esto se reportara
+    /// cada solucion aceptada y cada mejora a los observadores y se calculara el promedio de las soluciones.
+    /// Al terminar el lote se reporta el progreso a los observadores, los cuales pueden solicitar detener el recocido.
+    /// Si se cancela el recocido el lote se abandona sin registrarse.
     ///
+    #[allow(clippy::assign_op_pattern)]
     fn calcular_lote(&mut self) {
         let mut c: i64 = 0;
         let mut r: f64 = 0.0;
         let mut i: i64 = 0;
-        let l = 7000;
+        let l = self.parametros.tamano_lote;
         
         while c < l {
+            if self.cancelacion.cancelado() {
+                return;
+            }
             let a = self.get_vecino();
             let b = self.get_vecino();
             let new_sol = self.intercambiar_ciudades(a as usize, b as usize);
@@ -82,22 +207,182 @@ impl Tsp {
             if new_sol < (self.peso_solucion_actual + self.temperatura) {
                 c = c+1;
                 r = r + new_sol;
-                self.soluciones_aceptadas.push(new_sol);
+                self.aceptadas += 1;
                 self.peso_solucion_actual = new_sol;
                 let mut es_mejora = false;
                 if new_sol < self.mejor_solucion {
                     self.mejor_solucion = new_sol;
                     self.mejor_solucion_arr = self.solucion_actual.clone();
                     es_mejora = true;
+                    for observador in &mut self.observadores {
+                        observador.nueva_mejor(self.aceptadas, self.mejor_solucion, &self.mejor_solucion_arr);
+                    }
+                }
+                self.traza.registrar(new_sol, es_mejora);
+                for observador in &mut self.observadores {
+                    observador.solucion_aceptada(new_sol, es_mejora);
                 }
-                self.soluciones_para_svg.push((new_sol, es_mejora));
             } else {
                 self.intercambiar_ciudades(a as usize,b as usize);
             }
-            i = i + 1;
+            i += 1;
         }
 
         self.promedio = r/(c as f64);
+        self.lotes += 1;
+        self.historial_lotes.push(Lote {
+            aceptadas: self.aceptadas,
+            temperatura: self.temperatura,
+            tasa_aceptacion: (c as f64)/(i as f64),
+        });
+
+        let progreso = self.progreso();
+        for observador in &mut self.observadores {
+            if observador.lote_completado(&progreso) == Control::Detener {
+                self.detenido = true;
+            }
+        }
+    }
+
+    /// Funcion encargada de obtener el estado actual del recocido.
+    ///
+    fn progreso(&self) -> Progreso {
+        Progreso {
+            lotes: self.lotes,
+            temperatura: self.temperatura,
+            costo_actual: self.peso_solucion_actual,
+            mejor: self.mejor_solucion,
+            tasa_aceptacion: self.historial_lotes.last().map(|l| l.tasa_aceptacion).unwrap_or(0.0),
+            aceptadas: self.aceptadas,
+        }
+    }
+
+    /// Funcion encargada de registrar un observador del recocido.
+    ///
+    /// Para consultar la informacion del observador al terminar se puede registrar compartido en un `Arc<Mutex<_>>`.
+    ///
+    /// # Example
+    /// ```
+    /// let instantaneas = Arc::new(Mutex::new(Instantaneas::new(100)));
+    /// tsp.agregar_observador(Arc::clone(&instantaneas));
+    /// ```
+    pub fn agregar_observador(&mut self, observador: impl Observador + 'static) {
+        self.observadores.push(Box::new(observador));
+    }
+
+    /// Funcion encargada de establecer la traza de soluciones aceptadas.
+    ///
+    /// El tsp es el unico dueño de la traza durante el recocido, por lo que registrar cada solucion aceptada no requiere sincronizacion.
+    /// Al terminar el recocido la traza se recupera con `tomar_traza`. Por omision la traza esta apagada.
+    ///
+    /// # Example
+    /// ```
+    /// tsp.establecer_traza(Traza::archivo("resultados/tsp_semilla_1_traza.bin")?);
+    /// tsp.aceptacion_por_umbrales();
+    /// let (puntos, paso) = tsp.tomar_traza().muestra(100_000)?;
+    /// ```
+    pub fn establecer_traza(&mut self, traza: Traza) {
+        self.traza = traza;
+    }
+
+    /// Funcion encargada de recuperar la traza de soluciones aceptadas, dejando apagada la traza del tsp.
+    ///
+    pub fn tomar_traza(&mut self) -> Traza {
+        std::mem::replace(&mut self.traza, Traza::Apagada)
+    }
+
+    /// Funcion encargada de indicar si el recocido fue detenido por algun observador.
+    ///
+    pub fn detenido(&self) -> bool {
+        self.detenido
+    }
+
+    /// Funcion encargada de establecer el token de cancelacion del recocido.
+    ///
+    /// # Example
+    /// ```
+    /// tsp.establecer_cancelacion(cancelacion.clone());
+    /// ```
+    pub fn establecer_cancelacion(&mut self, cancelacion: Cancelacion) {
+        self.cancelacion = cancelacion;
+    }
+
+    /// Funcion encargada de indicar si el recocido fue interrumpido por el token de cancelacion.
+    ///
+    pub fn interrumpido(&self) -> bool {
+        self.cancelacion.cancelado()
+    }
+
+    /// Funcion encargada de indicar si el recocido debe terminar, ya sea por un observador o por cancelacion.
+    ///
+    fn terminar(&self) -> bool {
+        self.detenido || self.cancelacion.cancelado()
+    }
+
+    /// Funcion encargada de obtener el punto de control con el estado actual del recocido.
+    ///
+    pub fn punto_control(&self) -> PuntoControl {
+        PuntoControl {
+            semilla: self.semilla,
+            posicion_rng: self.random.get_word_pos(),
+            parametros: self.parametros.clone(),
+            solucion_actual: self.solucion_actual.clone(),
+            peso_solucion_actual: self.peso_solucion_actual,
+            mejor_solucion_arr: self.mejor_solucion_arr.clone(),
+            mejor_solucion: self.mejor_solucion,
+            temperatura: self.temperatura,
+            temperatura_inicial: self.temperatura_inicial,
+            promedio: self.promedio,
+            umbral: self.umbral,
+            aceptadas: self.aceptadas,
+            lotes: self.lotes,
+            historial_lotes: self.historial_lotes.clone(),
+            detenido: self.detenido,
+        }
+    }
+
+    /// Funcion encargada de registrar la funcion a la que se entregan los puntos de control del recocido.
+    ///
+    /// Recibe como parametros lo siguiente:
+    /// - cada:usize : Cantidad de lotes entre cada punto de control.
+    /// - funcion : Funcion que recibe el punto de control, por ejemplo para escribirlo en un archivo.
+    ///
+    /// Los puntos de control se toman unicamente al terminar un lote completo, un lote abandonado por cancelacion no genera punto de control.
+    ///
+    /// # Example
+    /// ```
+    /// tsp.guardar_puntos_control(1000, |p| p.escribir_json("punto_control.json").unwrap());
+    /// ```
+    pub fn guardar_puntos_control(&mut self, cada: usize, funcion: impl FnMut(&PuntoControl) + Send + 'static) {
+        self.puntos_control = Some((cada.max(1), Box::new(funcion)));
+    }
+
+    /// Funcion encargada de entregar el punto de control si el ultimo lote lo requiere.
+    ///
+    fn revisar_punto_control(&mut self) {
+        if self.cancelacion.cancelado() {
+            return;
+        }
+        if let Some((cada, _)) = &self.puntos_control && self.lotes.is_multiple_of(*cada) {
+            let punto = self.punto_control();
+            if let Some((_, funcion)) = &mut self.puntos_control {
+                funcion(&punto);
+            }
+        }
+    }
+
+    /// Funcion encargada de registrar la funcion a la que se reporta el progreso del recocido.
+    ///
+    /// Recibe como parametros lo siguiente:
+    /// - cada:usize : Cantidad de lotes entre cada reporte.
+    /// - funcion : Funcion que recibe el progreso.
+    ///
+    /// # Example
+    /// ```
+    /// tsp.reportar_progreso(100, |p| println!("Lote {} Temperatura {}", p.lotes, p.temperatura));
+    /// ```
+    pub fn reportar_progreso(&mut self, cada: usize, funcion: impl FnMut(&Progreso) + Send + 'static) {
+        self.agregar_observador(ReporteProgreso::new(cada, funcion));
     }
 
     /// Funcion encargada de calcular el peso de la solución actual.
@@ -110,34 +395,41 @@ impl Tsp {
     /// let peso_solucion = tsp.calcular_solucion();
     /// ```
     pub fn calcular_solucion(&mut self) -> f64 {
-        let mut i: usize = 0;
-        let mut j: usize = 1;
-        let mut res: f64 = 0.0;
-        
-        while j < self.solucion_actual.len()  {
-            res = res + self.grafica.peso(self.solucion_actual[i], self.solucion_actual[j]);
-            i = i+1;
-            j = j+1;
-        }
+        costo_recorrido(self.instancia, &self.solucion_actual)
+    }
 
-        let s:f64 = res/self.normalizador;
-        return s;
+    /// Funcion encargada de obtener la instancia del sistema.
+    ///
+    pub fn get_instancia(&self) -> &'a Instancia {
+        self.instancia
     }
 
-    /// Funcion encargada de obtener el normalizador.
+    /// Funcion encargada de establecer la solucion actual a partir de un recorrido y calcular su peso.
     ///
-    /// La funcion recibe la lista ordenada de distancias y el tamaño de las ciudades. De esta manera es que obtenemos n maximos valores de la lista, los sumamos y ese será el valor que devolveremos
+    /// Recibe como parametro el recorrido, el cual debe ser una permutacion de las ciudades de la instancia.
     ///
-    /// Regresa el normalizador en forma f64.
+    /// # Example
+    /// ```
+    /// tsp.establecer_recorrido(resultado.solucion.clone());
+    /// tsp.barrido();
+    /// ```
+    pub fn establecer_recorrido(&mut self, recorrido: Vec<i64>) {
+        self.solucion_actual = recorrido;
+        self.peso_solucion_actual = self.calcular_solucion();
+    }
+
+    /// Funcion encargada de obtener la semilla con la que se inicializo el random.
     ///
-    fn get_normalizador(lista_ordenada: &Vec<f64>, n: usize) -> f64 {
-        let mut i = lista_ordenada.len() - n + 1;
-        let mut normalizador: f64 = 0.0;
-        while i < lista_ordenada.len(){
-            normalizador = normalizador + lista_ordenada[i];
-            i = i+1;
-        }
-        return normalizador;
+    pub fn get_semilla(&self) -> i64 {
+        self.semilla
+    }
+
+    /// Funcion encargada de obtener la temperatura actual del sistema.
+    ///
+    /// Al terminar el recocido corresponde a la temperatura final.
+    ///
+    pub fn get_temperatura(&self) -> f64 {
+        self.temperatura
     }
 
     /// Funcion encargada de generar la primer solucion.
@@ -149,6 +441,7 @@ impl Tsp {
     /// ```
     /// tsp.generar_primer_solucion();
     /// ```
+    #[allow(clippy::assign_op_pattern)]
     pub fn generar_primer_solucion(&mut self) {
         let mut i: i64 = 0;
         while i < (self.solucion_actual.len() as i64) {
@@ -165,58 +458,14 @@ impl Tsp {
     /// - b:usize : Corresponde al indice de la segunda ciudad.
     ///
     fn intercambiar_ciudades(&mut self, a: usize, b: usize) -> f64{
-        let temp = self.solucion_actual[a];
-        let mut solucion = self.peso_solucion_actual;
-
-        solucion = solucion * self.normalizador;
-        
-        if a != 0 && a!= self.solucion_actual.len()-1 {
-            solucion = solucion - self.grafica.peso(self.solucion_actual[a-1], self.solucion_actual[a]);
-            solucion = solucion -  self.grafica.peso(self.solucion_actual[a], self.solucion_actual[a+1]);
-        } else if a != 0 {
-            solucion = solucion - self.grafica.peso(self.solucion_actual[a-1], self.solucion_actual[a]);
-        } else {
-            solucion = solucion -  self.grafica.peso(self.solucion_actual[a], self.solucion_actual[a+1]);
-        }
-
-        if b != 0 && b != self.solucion_actual.len()-1 {
-            solucion = solucion - self.grafica.peso(self.solucion_actual[b-1], self.solucion_actual[b]);
-            solucion = solucion - self.grafica.peso(self.solucion_actual[b], self.solucion_actual[b+1]);
-        } else if b != 0 {
-            solucion = solucion - self.grafica.peso(self.solucion_actual[b-1], self.solucion_actual[b]);
-        } else {
-            solucion = solucion - self.grafica.peso(self.solucion_actual[b], self.solucion_actual[b+1]);
-        }
-        
-        self.solucion_actual[a] = self.solucion_actual[b];
-        self.solucion_actual[b] = temp;
-
-        if a != 0 && a!= self.solucion_actual.len()-1 {
-            solucion = solucion + self.grafica.peso(self.solucion_actual[a-1], self.solucion_actual[a]);
-            solucion = solucion +  self.grafica.peso(self.solucion_actual[a], self.solucion_actual[a+1]);
-        } else if a != 0 {
-            solucion = solucion + self.grafica.peso(self.solucion_actual[a-1], self.solucion_actual[a]);
-        } else {
-            solucion = solucion +  self.grafica.peso(self.solucion_actual[a], self.solucion_actual[a+1]);
-        }
-
-        if b != 0 && b != self.solucion_actual.len()-1 {
-            solucion = solucion + self.grafica.peso(self.solucion_actual[b-1], self.solucion_actual[b]);
-            solucion = solucion + self.grafica.peso(self.solucion_actual[b], self.solucion_actual[b+1]);
-        } else if b != 0 {
-            solucion = solucion + self.grafica.peso(self.solucion_actual[b-1], self.solucion_actual[b]);
-        } else {
-            solucion = solucion + self.grafica.peso(self.solucion_actual[b], self.solucion_actual[b+1]);
-        }
-
-        solucion = solucion/self.normalizador;
-        return solucion;
+        intercambiar(self.instancia, &mut self.solucion_actual, self.peso_solucion_actual, a, b)
     }
 
     /// Funcion encargada de obtener un vecido aleatorio.
     ///
     /// Regresa el indice de la ciudad en el rango de las ciudades.
     ///
+    #[allow(clippy::needless_return)]
     fn get_vecino(&mut self) -> i64 {
         
         return self.random.random_range(0..self.solucion_actual.len()) as i64;
@@ -225,30 +474,61 @@ impl Tsp {
     /// Funcion encargada de ejecutar el algoritmo de aceptacion por umbrales
     ///
     /// Genera soluciones a partir de una temperatura, la temperatura ira disminuyendo durante cada ejecucion del algoritmo una vez que el calculo de un lote mejora el promedio de las soluciones.
+    /// Cada cambio de temperatura y el final del recocido se reportan a los observadores. El recocido termina antes si algun observador lo solicita
+    /// o si se cancela el token de cancelacion, conservando la mejor solucion encontrada hasta ese momento.
+    /// Si se solicitaron, al terminar cada cierta cantidad de lotes se entrega un punto de control.
     ///
     /// # Example
     /// ```
     /// tsp.aceptacion_por_umbrales();
     /// ```
+    #[allow(clippy::let_unit_value)]
     pub fn aceptacion_por_umbrales (&mut self) {
 
-        let e: f64 = 0.0001;
-        let phi: f64 = 0.95;
-        let _ = self.temperatura_inicial();
+        let _ = self.calcular_temperatura_inicial();
+        self.temperatura_inicial = self.temperatura;
         self.promedio = 0.0;
         self.generar_primer_solucion();
         self.peso_solucion_actual = self.calcular_solucion();
-        while self.temperatura > e {
-            let mut q = f64::MAX;
-            while self.promedio <= q{
-                q = self.promedio;
+        self.umbral = f64::MAX;
+        self.reanudar_recocido();
+    }
+
+    /// Funcion encargada de continuar el algoritmo de aceptacion por umbrales desde el estado actual.
+    ///
+    /// Se usa para continuar un recocido restaurado con `desde_punto_control`, obteniendo el mismo resultado que sin la interrupcion.
+    ///
+    /// # Example
+    /// ```
+    /// let mut tsp = Tsp::desde_punto_control(punto, &instancia);
+    /// tsp.reanudar_recocido();
+    /// ```
+    #[allow(clippy::assign_op_pattern)]
+    pub fn reanudar_recocido(&mut self) {
+        let e: f64 = self.parametros.epsilon;
+        let phi: f64 = self.parametros.phi;
+        while self.temperatura > e && !self.terminar() {
+            while self.promedio <= self.umbral && !self.terminar() {
+                self.umbral = self.promedio;
                 self.calcular_lote();
+                self.revisar_punto_control();
+            }
+            if self.terminar() {
+                break;
             }
             
+            let anterior = self.temperatura;
             self.temperatura = self.temperatura * phi;
-            
+            self.umbral = f64::MAX;
+            for observador in &mut self.observadores {
+                observador.temperatura_cambiada(anterior, self.temperatura);
+            }
 
         } 
+        let progreso = self.progreso();
+        for observador in &mut self.observadores {
+            observador.ejecucion_terminada(&progreso);
+        }
     }
 
     /// Funcion encargada de ejecutar el algoritmo de barrido.
@@ -260,25 +540,7 @@ impl Tsp {
     /// tsp.barrido();
     /// ```
     pub fn barrido(&mut self){
-        self.peso_solucion_actual = self.calcular_solucion();
-        let mut a:usize = 0;
-        while a < self.solucion_actual.len(){
-            let mut i = a+1;
-            while i < self.solucion_actual.len() {
-                let c = self.peso_solucion_actual;
-                let d = self.intercambiar_ciudades(a,i);
-            
-                if d < c {
-                    self.peso_solucion_actual = d;
-                    self.barrido();
-                    return;
-                }
-                let _ = self.intercambiar_ciudades(a,i);
-                i+=1;
-            }
-            a+=1;
-        }
-        return;
+        self.peso_solucion_actual = barrido_recorrido(self.instancia, &mut self.solucion_actual);
     }
 
     /// Funcion encargada de calcular la temperatura inicial.
@@ -286,8 +548,9 @@ impl Tsp {
     /// La función se encarga de calcular la temperatura inicial que nos proveera de un porcentaje de aceptacion}
     /// de las soluciones en el rango que nosotros proporcionamos, dicho esto se realiza haciendo uso de busqueda binaria.
     ///
-    fn temperatura_inicial (&mut self) {
-        let porc = 0.60;
+    #[allow(clippy::assign_op_pattern)]
+    fn calcular_temperatura_inicial (&mut self) {
+        let porc = self.parametros.porcentaje;
         let t1:f64;
         let t2:f64;
         let mut t:f64 = self.temperatura;
@@ -318,6 +581,7 @@ impl Tsp {
     ///
     /// La función se encarga de calcular el porcentaje de soluciones aceptadas para un tamaño de lote especifico
     /// 
+    #[allow(clippy::needless_return)]
     fn porcentajes_aceptados(&mut self, t:f64) -> f64{
         let mut c = 0;
         let mut i = 1;
@@ -345,6 +609,7 @@ impl Tsp {
     ///
     /// Regresa la temperatura encontrada.
     ///
+    #[allow(clippy::needless_return)]
     fn busqueda_binaria(&mut self, t1:f64, t2:f64, porc:f64) -> f64{
         let tm = (t1 + t2)/2.0;
         if t2 - t1 < 0.0001 {
@@ -362,36 +627,144 @@ impl Tsp {
     }
 }
 
+/// Funcion encargada de calcular el costo de un recorrido.
+///
+/// Suma el peso entre cada par de ciudades consecutivas del recorrido y lo divide entre el normalizador de la instancia.
+///
+/// # Example
+/// ```
+/// let costo = costo_recorrido(&instancia, &recorrido);
+/// ```
+#[allow(clippy::assign_op_pattern, clippy::needless_return)]
+pub fn costo_recorrido(instancia: &Instancia, recorrido: &[i64]) -> f64 {
+    let mut i: usize = 0;
+    let mut j: usize = 1;
+    let mut res: f64 = 0.0;
+
+    while j < recorrido.len()  {
+        res = res + instancia.peso(recorrido[i], recorrido[j]);
+        i = i+1;
+        j = j+1;
+    }
+
+    let s:f64 = res/instancia.normalizador;
+    return s;
+}
+
+/// Funcion encargada de intercambiar dos ciudades de un recorrido.
+///
+/// Recibe como parametros lo siguiente:
+/// - instancia:&Instancia : Instancia con la que se calculan los pesos.
+/// - recorrido:`&mut [i64]` : Recorrido en el que se intercambian las ciudades.
+/// - peso:f64 : Costo del recorrido antes del intercambio.
+/// - a:usize : Corresponde al indice de la primer ciudad.
+/// - b:usize : Corresponde al indice de la segunda ciudad.
+///
+/// Regresa el costo del recorrido despues del intercambio, calculado restando y sumando unicamente las aristas que cambian.
+///
+#[allow(clippy::assign_op_pattern, clippy::needless_return)]
+fn intercambiar(instancia: &Instancia, recorrido: &mut [i64], peso: f64, a: usize, b: usize) -> f64 {
+    let temp = recorrido[a];
+    let mut solucion = peso;
+
+    solucion = solucion * instancia.normalizador;
+
+    if a != 0 && a!= recorrido.len()-1 {
+        solucion = solucion - instancia.peso(recorrido[a-1], recorrido[a]);
+        solucion = solucion - instancia.peso(recorrido[a], recorrido[a+1]);
+    } else if a != 0 {
+        solucion = solucion - instancia.peso(recorrido[a-1], recorrido[a]);
+    } else {
+        solucion = solucion - instancia.peso(recorrido[a], recorrido[a+1]);
+    }
+
+    if b != 0 && b != recorrido.len()-1 {
+        solucion = solucion - instancia.peso(recorrido[b-1], recorrido[b]);
+        solucion = solucion - instancia.peso(recorrido[b], recorrido[b+1]);
+    } else if b != 0 {
+        solucion = solucion - instancia.peso(recorrido[b-1], recorrido[b]);
+    } else {
+        solucion = solucion - instancia.peso(recorrido[b], recorrido[b+1]);
+    }
+
+    recorrido[a] = recorrido[b];
+    recorrido[b] = temp;
+
+    if a != 0 && a!= recorrido.len()-1 {
+        solucion = solucion + instancia.peso(recorrido[a-1], recorrido[a]);
+        solucion = solucion + instancia.peso(recorrido[a], recorrido[a+1]);
+    } else if a != 0 {
+        solucion = solucion + instancia.peso(recorrido[a-1], recorrido[a]);
+    } else {
+        solucion = solucion + instancia.peso(recorrido[a], recorrido[a+1]);
+    }
+
+    if b != 0 && b != recorrido.len()-1 {
+        solucion = solucion + instancia.peso(recorrido[b-1], recorrido[b]);
+        solucion = solucion + instancia.peso(recorrido[b], recorrido[b+1]);
+    } else if b != 0 {
+        solucion = solucion + instancia.peso(recorrido[b-1], recorrido[b]);
+    } else {
+        solucion = solucion + instancia.peso(recorrido[b], recorrido[b+1]);
+    }
+
+    solucion = solucion/instancia.normalizador;
+    return solucion;
+}
+
+/// Funcion encargada de ejecutar el algoritmo de barrido sobre un recorrido.
+///
+/// Prueba el intercambio de cada par de ciudades del recorrido; en cuanto un intercambio mejora el costo, lo conserva y vuelve
+/// a empezar desde el primer par con el costo recalculado. Termina cuando ningun intercambio mejora el recorrido y regresa su costo.
+///
+/// # Example
+/// ```
+/// let costo = barrido_recorrido(&instancia, &mut recorrido);
+/// ```
+pub fn barrido_recorrido(instancia: &Instancia, recorrido: &mut [i64]) -> f64 {
+    'barrido: loop {
+        let peso = costo_recorrido(instancia, recorrido);
+        for a in 0..recorrido.len() {
+            for i in a + 1..recorrido.len() {
+                if intercambiar(instancia, recorrido, peso, a, i) < peso {
+                    continue 'barrido;
+                }
+                let _ = intercambiar(instancia, recorrido, peso, a, i);
+            }
+        }
+        return peso;
+    }
+}
+
+#[allow(clippy::empty_line_after_outer_attr)]
 #[cfg(test)]
 
 mod tests {
     use super::*;
     use std::fs;
-    use crate::db::CityDB;
-    use crate::grafica::Grafica;
-    fn generar_tsp(ruta: String) -> Tsp {
+    use crate::instancia::Metrica;
+    fn generar_instancia_db(ruta: String) -> Instancia {
 
-        let contenido = fs::read_to_string(ruta);
+        let contenido = fs::read_to_string(&ruta);
 
         let numeros: Vec<i64> = contenido.expect("No es un entero").trim().split(',').map(|s| s.parse::<i64>().expect("Error al convertir el numero")).collect();
 
-        let mut cities = CityDB::new(&numeros);
-
-        let _ = cities.cargar_datos();
-
-        let g = Grafica::new(cities);
-
         println!("Arreglo: {:?}", numeros);
 
-        let tsp = Tsp::new(1000.0, g, numeros, 75);
-
-        return tsp;
+        Instancia::cargar_db(&ruta, &numeros).unwrap()
         
     }
+
+    fn generar_instancia(n: i64) -> Instancia {
+        let ciudades: Vec<i64> = (1..=n).collect();
+        let coordenadas = ciudades.iter().map(|c| (*c as f64, (c * c) as f64)).collect();
+        Instancia::desde_coordenadas("prueba", ciudades, coordenadas, Metrica::Euclidiana)
+    }
     
     #[test]
     fn ok_calcular_solucion() {        
-        let mut tsp:Tsp = generar_tsp("inputs/input-40.tsp".to_string());
+        let instancia = generar_instancia_db("inputs/input-40.tsp".to_string());
+        let mut tsp:Tsp = Tsp::new(1000.0, &instancia, 75);
         assert_eq!(tsp.calcular_solucion(), 7598476.968976471);
 
         //let mut tsp = generar_tsp("inputs/input-150.tsp".to_string());
@@ -399,9 +772,130 @@ mod tests {
         
     }
 
+    #[test]
+    fn ok_reportar_progreso() {
+        use std::sync::{Arc, Mutex};
+        let instancia = generar_instancia(4);
+        let mut tsp = Tsp::new(1000.0, &instancia, 75);
+        tsp.parametros.tamano_lote = 10;
+        let reportes = Arc::new(Mutex::new(Vec::new()));
+        let copia = Arc::clone(&reportes);
+        tsp.reportar_progreso(2, move |p| copia.lock().unwrap().push(p.lotes));
+        tsp.peso_solucion_actual = tsp.calcular_solucion();
+        for _ in 0..5 {
+            tsp.calcular_lote();
+        }
+        assert_eq!(*reportes.lock().unwrap(), vec![2, 4]);
+    }
+
+    #[test]
+    fn ok_traza() {
+        use crate::traza::Decimador;
+        let instancia = generar_instancia(5);
+        let mut tsp = Tsp::new(1000.0, &instancia, 75);
+        tsp.parametros.tamano_lote = 10;
+        tsp.establecer_traza(Traza::Memoria(Decimador::new(1000)));
+        tsp.peso_solucion_actual = tsp.calcular_solucion();
+        tsp.calcular_lote();
+        let (puntos, paso) = tsp.tomar_traza().muestra(1000).unwrap();
+        assert_eq!((puntos.len(), paso), (tsp.aceptadas, 1));
+        assert_eq!(tsp.tomar_traza().muestra(1000).unwrap(), (Vec::new(), 1));
+    }
+
+    #[test]
+    fn ok_cancelacion() {
+        let instancia = generar_instancia(6);
+        let mut tsp = Tsp::new(1000.0, &instancia, 75);
+        tsp.parametros.tamano_lote = 20;
+        let cancelacion = Cancelacion::new();
+        tsp.establecer_cancelacion(cancelacion.clone());
+        let copia = cancelacion.clone();
+        tsp.reportar_progreso(2, move |_| copia.cancelar());
+        tsp.aceptacion_por_umbrales();
+        assert!(tsp.interrumpido());
+        assert_eq!(tsp.lotes, 2);
+        assert_eq!(tsp.aceptadas, 40);
+        assert!(tsp.mejor_solucion < f64::MAX);
+    }
+
+    #[test]
+    fn ok_cancelacion_temperatura() {
+        use std::sync::{Arc, Mutex};
+        struct Temperaturas(Vec<f64>);
+        impl Observador for Temperaturas {
+            fn temperatura_cambiada(&mut self, _anterior: f64, nueva: f64) {
+                self.0.push(nueva);
+            }
+        }
+        let instancia = generar_instancia(6);
+        let mut tsp = Tsp::new(1000.0, &instancia, 75);
+        tsp.parametros.tamano_lote = 20;
+        let temperaturas = Arc::new(Mutex::new(Temperaturas(Vec::new())));
+        tsp.agregar_observador(Arc::clone(&temperaturas));
+        let cancelacion = Cancelacion::new();
+        tsp.establecer_cancelacion(cancelacion.clone());
+        tsp.reportar_progreso(1, move |p| if p.lotes == 40 { cancelacion.cancelar() });
+        tsp.aceptacion_por_umbrales();
+        assert!(tsp.interrumpido());
+        let ultimo = tsp.historial_lotes.last().unwrap().temperatura;
+        assert_eq!(tsp.get_temperatura(), ultimo);
+        let cambios = tsp.historial_lotes.windows(2).filter(|par| par[0].temperatura != par[1].temperatura).count();
+        let temperaturas = &temperaturas.lock().unwrap().0;
+        assert!(cambios > 0);
+        assert_eq!(temperaturas.len(), cambios);
+        assert_eq!(*temperaturas.last().unwrap(), ultimo);
+    }
+
+    #[test]
+    fn ok_punto_control() {
+        use std::sync::{Arc, Mutex};
+        struct Limite(usize);
+        impl Observador for Limite {
+            fn lote_completado(&mut self, progreso: &Progreso) -> Control {
+                if progreso.lotes >= self.0 { Control::Detener } else { Control::Continuar }
+            }
+        }
+        let instancia = generar_instancia(6);
+        let mut parametros = Parametros { temperatura: 1000.0, ..Parametros::default() };
+        parametros.tamano_lote = 20;
+
+        let mut completo = Tsp::con_parametros(parametros.clone(), &instancia, 75);
+        completo.agregar_observador(Limite(30));
+        completo.aceptacion_por_umbrales();
+
+        let puntos = Arc::new(Mutex::new(Vec::new()));
+        let copia = Arc::clone(&puntos);
+        let mut interrumpido = Tsp::con_parametros(parametros, &instancia, 75);
+        let cancelacion = Cancelacion::new();
+        interrumpido.establecer_cancelacion(cancelacion.clone());
+        interrumpido.agregar_observador(Limite(30));
+        interrumpido.guardar_puntos_control(5, move |p| {
+            copia.lock().unwrap().push(serde_json::to_string(p).unwrap());
+            if p.lotes == 10 {
+                cancelacion.cancelar();
+            }
+        });
+        interrumpido.aceptacion_por_umbrales();
+        assert!(interrumpido.interrumpido());
+
+        let punto: PuntoControl = serde_json::from_str(puntos.lock().unwrap().last().unwrap()).unwrap();
+        assert_eq!(punto.lotes, 10);
+        let mut reanudado = Tsp::desde_punto_control(punto, &instancia);
+        reanudado.agregar_observador(Limite(30));
+        reanudado.reanudar_recocido();
+        assert_eq!(completo.lotes, 30);
+        assert_eq!(reanudado.lotes, completo.lotes);
+        assert_eq!(reanudado.aceptadas, completo.aceptadas);
+        assert_eq!(reanudado.mejor_solucion.to_bits(), completo.mejor_solucion.to_bits());
+        assert_eq!(reanudado.mejor_solucion_arr, completo.mejor_solucion_arr);
+        assert_eq!(reanudado.solucion_actual, completo.solucion_actual);
+        assert_eq!(reanudado.historial_lotes, completo.historial_lotes);
+    }
+
     #[test]
     fn ok_intercambiar_ciudades() {
-        let mut tsp:Tsp = generar_tsp("inputs/input-40.tsp".to_string());
+        let instancia = generar_instancia_db("inputs/input-40.tsp".to_string());
+        let mut tsp:Tsp = Tsp::new(1000.0, &instancia, 75);
         let res1 = tsp.calcular_solucion();
         tsp.peso_solucion_actual = res1;
         let res = tsp.intercambiar_ciudades(5,20);